@@ -0,0 +1,100 @@
+# English, which every other language falls back to for anything it's missing
+
+## Headers
+
+header-black-turn = Black's Turn
+header-black-foresight = Choose your next move
+header-red-turn = Your Turn
+header-red-correction = Your chosen move was invalidated by Black, choose another
+header-paused = Paused
+header-invalid-destination = You cannot move that checker to that position
+header-forced-capture = You must take any available jumps
+header-not-a-move = { $move } is not a move you can make
+
+## Menus
+
+menu-new-game = New Game
+menu-continue = Continue
+menu-variant = Variant
+menu-difficulty = Difficulty
+menu-settings = Settings
+menu-quit = Quit
+pause-resume = Resume
+pause-restart = Restart
+pause-quit-to-menu = Quit to Menu
+
+variant-standard = English draughts
+variant-optional-captures = Optional captures
+
+difficulty-easy = Easy
+difficulty-normal = Normal
+difficulty-hard = Hard
+
+## Settings
+
+settings-volume = Master volume
+settings-sfx-volume = Effects volume
+settings-music-volume = Music volume
+settings-camera-sensitivity = Camera sensitivity
+settings-orbit-camera = Orbit camera (right drag, scroll)
+settings-animation-speed = Animation speed
+settings-text-speed = Text speed
+settings-typing-speed = Typing speed
+settings-typing-sounds = Typing sounds
+settings-skip-intro = Skip intro
+settings-theme = Colour theme
+settings-fullscreen = Fullscreen
+settings-language = Language
+settings-back = Back
+
+theme-dark = Dark
+theme-light = Light
+
+## Move history
+
+history-moves = Moves
+history-foretold = Foretold
+history-invalidated = Foretold, but invalidated by Black
+history-back = Back to the game
+seed = Seed { $seed }
+text-log = What was said
+
+## Intro
+
+intro-play-a-game = Let's play a game.
+intro-checkers = You know checkers, right?
+intro-draughts = Or draughts. Whatever you prefer.
+intro-boring = But that'd be boring, wouldn't it?
+intro-twist = Let's add a twist.
+intro-tell-me = You must tell me your next move before I make mine.
+intro-black = I'll take black, so I go first.
+
+## Endings
+
+red-wins-beaten = Oh. It appears you've beaten me.
+red-wins-you-know = You know,
+red-wins-future = for someone that can see the future,
+red-wins-plan-ahead = you don't plan ahead an awful lot.
+red-wins-ellipsis = ...
+black-wins-won = Aha. I've won.
+black-wins-again = Let's play again.
+
+## Remarks during play
+
+remark-correction-1 = Did you really think I'd let you do that?
+remark-correction-2 = I saw that coming. Literally.
+remark-correction-3 = Not that one. Try again.
+remark-own-multi-capture-1 = Two for the price of one.
+remark-own-multi-capture-2 = You left those wide open.
+remark-player-multi-capture-1 = Hm. I didn't see that far ahead.
+remark-player-multi-capture-2 = Lucky.
+remark-own-king-1 = Crown me.
+remark-own-king-2 = I'll be taking this one back with me.
+remark-player-king-1 = Enjoy it while it lasts.
+remark-player-king-2 = A king. How regal.
+remark-ahead-1 = This is going rather well. For me.
+remark-ahead-2 = You can't win if you keep telling me your moves.
+remark-behind-1 = I'm only letting you win.
+remark-behind-2 = Don't get comfortable.
+remark-long-think-1 = Take your time. I already know what you'll do.
+remark-long-think-2 = Any day now.
@@ -0,0 +1,100 @@
+# French. Anything missing here is shown in English.
+
+## Headers
+
+header-black-turn = Au tour des noirs
+header-black-foresight = Choisissez votre prochain coup
+header-red-turn = À vous de jouer
+header-red-correction = Les noirs ont rendu votre coup impossible, choisissez-en un autre
+header-paused = Pause
+header-invalid-destination = Ce pion ne peut pas aller à cette case
+header-forced-capture = Vous devez prendre dès que possible
+header-not-a-move = { $move } n'est pas un coup possible
+
+## Menus
+
+menu-new-game = Nouvelle partie
+menu-continue = Continuer
+menu-variant = Variante
+menu-difficulty = Difficulté
+menu-settings = Paramètres
+menu-quit = Quitter
+pause-resume = Reprendre
+pause-restart = Recommencer
+pause-quit-to-menu = Retour au menu
+
+variant-standard = Dames anglaises
+variant-optional-captures = Prise facultative
+
+difficulty-easy = Facile
+difficulty-normal = Normal
+difficulty-hard = Difficile
+
+## Settings
+
+settings-volume = Volume général
+settings-sfx-volume = Volume des effets
+settings-music-volume = Volume de la musique
+settings-camera-sensitivity = Sensibilité de la caméra
+settings-orbit-camera = Caméra orbitale (clic droit, molette)
+settings-animation-speed = Vitesse des animations
+settings-text-speed = Vitesse du texte
+settings-typing-speed = Vitesse de frappe
+settings-typing-sounds = Sons de frappe
+settings-skip-intro = Passer l'introduction
+settings-theme = Thème
+settings-fullscreen = Plein écran
+settings-language = Langue
+settings-back = Retour
+
+theme-dark = Sombre
+theme-light = Clair
+
+## Move history
+
+history-moves = Coups
+history-foretold = Annoncé
+history-invalidated = Annoncé, mais rendu impossible par les noirs
+history-back = Revenir à la partie
+seed = Graine { $seed }
+text-log = Ce qui a été dit
+
+## Intro
+
+intro-play-a-game = Jouons à un jeu.
+intro-checkers = Vous connaissez les dames, n'est-ce pas ?
+intro-draughts = Ou le jeu de dames. Comme vous préférez.
+intro-boring = Mais ce serait ennuyeux, non ?
+intro-twist = Ajoutons une petite règle.
+intro-tell-me = Vous devez m'annoncer votre prochain coup avant que je joue le mien.
+intro-black = Je prends les noirs, donc je commence.
+
+## Endings
+
+red-wins-beaten = Oh. On dirait que vous m'avez battu.
+red-wins-you-know = Vous savez,
+red-wins-future = pour quelqu'un qui voit l'avenir,
+red-wins-plan-ahead = vous ne prévoyez pas grand-chose.
+red-wins-ellipsis = ...
+black-wins-won = Ha. J'ai gagné.
+black-wins-again = On rejoue.
+
+## Remarks during play
+
+remark-correction-1 = Vous pensiez vraiment que j'allais vous laisser faire ?
+remark-correction-2 = Je l'avais vu venir. Littéralement.
+remark-correction-3 = Pas celui-là. Essayez encore.
+remark-own-multi-capture-1 = Deux pour le prix d'un.
+remark-own-multi-capture-2 = Vous les aviez laissés sans défense.
+remark-player-multi-capture-1 = Hm. Je n'avais pas vu aussi loin.
+remark-player-multi-capture-2 = Quelle chance.
+remark-own-king-1 = Couronnez-moi.
+remark-own-king-2 = Celle-là, je la garde.
+remark-player-king-1 = Profitez-en tant que ça dure.
+remark-player-king-2 = Une dame. Comme c'est royal.
+remark-ahead-1 = Ça se passe plutôt bien. Pour moi.
+remark-ahead-2 = Vous ne pouvez pas gagner si vous m'annoncez vos coups.
+remark-behind-1 = Je vous laisse gagner, c'est tout.
+remark-behind-2 = Ne vous y habituez pas.
+remark-long-think-1 = Prenez votre temps. Je sais déjà ce que vous allez faire.
+remark-long-think-2 = On attend.
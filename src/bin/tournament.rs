@@ -0,0 +1,205 @@
+//! Plays two AI configurations against each other, headless, and reports how they fared.
+//!
+//! Usage: tournament [--games N] [--seed S] [--max-moves N] [--pdn PATH] <CONFIG_A> <CONFIG_B>
+//!
+//! A config is a difficulty (`easy`, `normal`, `hard`) or a list of settings like `depth=3,foresight=0.5`,
+//! see `AiConfig::parse`. The configs swap colours every game, since Red foretelling its moves makes the sides uneven.
+
+use foretold::game::Team;
+use foretold::game::ai::*;
+use foretold::game::notation::*;
+use foretold::rng::GameRng;
+
+struct Options {
+    games: u32,
+    seed: u64,
+    max_moves: u32,
+    pdn: String,
+    configs: [(String, AiConfig); 2],
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut games = 1000;
+    let mut seed = 0;
+    let mut max_moves = 200;
+    let mut pdn = "tournament.pdn".to_string();
+    let mut configs = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("Missing value for {}", name));
+
+        match arg.as_str() {
+            "--games" => games = value("--games")?.parse().map_err(|_| "Invalid --games")?,
+            "--seed" => seed = value("--seed")?.parse().map_err(|_| "Invalid --seed")?,
+            "--max-moves" => max_moves = value("--max-moves")?.parse().map_err(|_| "Invalid --max-moves")?,
+            "--pdn" => pdn = value("--pdn")?,
+            _ => configs.push((arg.clone(), AiConfig::parse(&arg)?)),
+        }
+    }
+
+    if configs.len() != 2 {
+        return Err("Expected exactly two AI configs".to_string());
+    }
+
+    let b = configs.pop().unwrap();
+    let a = configs.pop().unwrap();
+
+    Ok(Options { games, seed, max_moves, pdn, configs: [a, b] })
+}
+
+struct GameRecord {
+    moves: Vec<PlayedMove>,
+    /// `None` for a draw
+    winner: Option<Team>,
+}
+
+/// Plays one game the same way the real one flows: Red foretells a move, Black replies knowing it,
+/// then Red plays the foretold move or, if Black invalidated it, a correction.
+fn play_game(black: &AiConfig, red: &AiConfig, max_moves: u32, rng: &mut GameRng) -> GameRecord {
+    let mut board = Board::initial();
+    let mut moves = vec![];
+
+    for _ in 0..max_moves {
+        let foretold = match choose_red_move(&board, red, rng) {
+            Some(mov) => mov,
+            None => return GameRecord { moves, winner: Some(Team::Black) },
+        };
+
+        let reply = match choose_black_move(&board, Some(&foretold), black, rng) {
+            Some(mov) => mov,
+            None => return GameRecord { moves, winner: Some(Team::Red) },
+        };
+
+        board.apply(&reply);
+        moves.push(PlayedMove { team: Team::Black, mov: reply, replaced: None });
+
+        if board.can_play(&foretold, Team::Red) {
+            board.apply(&foretold);
+            moves.push(PlayedMove { team: Team::Red, mov: foretold, replaced: None });
+        } else {
+            let correction = match choose_move(&board, Team::Red, red, rng) {
+                Some(mov) => mov,
+                None => return GameRecord { moves, winner: Some(Team::Black) },
+            };
+
+            board.apply(&correction);
+            moves.push(PlayedMove { team: Team::Red, mov: correction, replaced: Some(foretold) });
+        }
+    }
+
+    GameRecord { moves, winner: None }
+}
+
+#[derive(Default)]
+struct Tally {
+    wins: u32,
+    draws: u32,
+    losses: u32,
+}
+
+impl Tally {
+    fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    fn score(&self) -> f64 {
+        (self.wins as f64 + 0.5 * self.draws as f64) / self.games().max(1) as f64
+    }
+
+    /// Elo difference implied by the score, with a 95% margin of error
+    fn elo(&self) -> (f64, f64) {
+        let n = self.games().max(1) as f64;
+        let score = self.score();
+
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * (0.0 - score).powi(2)) / n;
+        let margin = 1.96 * (variance / n).sqrt();
+
+        let low = elo_from_score(score - margin);
+        let high = elo_from_score(score + margin);
+
+        (elo_from_score(score), (high - low) / 2.0)
+    }
+
+    fn report(&self, label: &str) {
+        let (elo, margin) = self.elo();
+        println!(
+            "{:<12} {:>6} games  +{} ={} -{}  score {:.1}%  Elo {:+.0} ± {:.0}",
+            label, self.games(), self.wins, self.draws, self.losses, self.score() * 100.0, elo, margin
+        );
+    }
+}
+
+fn elo_from_score(score: f64) -> f64 {
+    // A perfect score has no finite Elo difference, so cap it rather than print infinity
+    let score = score.clamp(0.001, 0.999);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: tournament [--games N] [--seed S] [--max-moves N] [--pdn PATH] <CONFIG_A> <CONFIG_B>");
+            std::process::exit(2);
+        }
+    };
+
+    let [(a_name, a), (b_name, b)] = &options.configs;
+
+    let mut pdn = String::new();
+    let mut total = Tally::default();
+    let mut as_black = Tally::default();
+    let mut as_red = Tally::default();
+
+    for game in 0..options.games {
+        let seed = options.seed.wrapping_add(game as u64);
+        let mut rng = GameRng::new(seed);
+
+        // A takes Black on even games and Red on odd ones
+        let a_team = if game % 2 == 0 { Team::Black } else { Team::Red };
+        let (black, red, black_name, red_name) = match a_team {
+            Team::Black => (a, b, a_name, b_name),
+            Team::Red => (b, a, b_name, a_name),
+        };
+
+        let record = play_game(black, red, options.max_moves, &mut rng);
+
+        let by_colour = match a_team {
+            Team::Black => &mut as_black,
+            Team::Red => &mut as_red,
+        };
+        for tally in [&mut total, by_colour] {
+            match record.winner {
+                Some(team) if team == a_team => tally.wins += 1,
+                Some(_) => tally.losses += 1,
+                None => tally.draws += 1,
+            }
+        }
+
+        pdn.push_str(&pdn_game(
+            &[
+                ("Event", "Foretold self-play".to_string()),
+                ("Round", (game + 1).to_string()),
+                ("Black", black_name.clone()),
+                ("White", red_name.clone()),
+                ("Seed", seed.to_string()),
+            ],
+            &record.moves,
+            record.winner,
+        ));
+    }
+
+    if let Err(e) = std::fs::write(&options.pdn, pdn) {
+        eprintln!("Failed to write {}: {}", options.pdn, e);
+    }
+
+    println!("{} vs {}", a_name, b_name);
+    total.report("Overall");
+    as_black.report("As Black");
+    as_red.report("As Red");
+    println!("Games written to {}", options.pdn);
+}
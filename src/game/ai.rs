@@ -0,0 +1,514 @@
+use super::*;
+use super::notation::Move;
+use std::collections::HashMap;
+
+/// Value of a finished game, well outside anything material can add up to
+const WIN_SCORE: f32 = 1000.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Piece {
+    pub team: Team,
+    pub king: bool,
+}
+
+/// A copy of the board that the AI can search and play on without touching any entities.
+#[derive(Clone, Debug, Default)]
+pub struct Board(HashMap<IVec2, Piece>, Variant);
+
+impl Board {
+    /// The position every game starts from
+    pub fn initial() -> Self {
+        let mut board = Board::default();
+
+        for i in 0..12 {
+            board.0.insert(get_initial_checkboard_coord(i, Team::Black), Piece { team: Team::Black, king: false });
+            board.0.insert(get_initial_checkboard_coord(i, Team::Red), Piece { team: Team::Red, king: false });
+        }
+
+        board
+    }
+
+    pub fn from_checkers<C: std::ops::Deref<Target = Checker>>(checkers: impl Iterator<Item = C>) -> Self {
+        Board(
+            checkers
+            .filter(|c| c.alive)
+            .map(|c| (c.pos, Piece { team: c.team, king: c.king }))
+            .collect(),
+            Variant::default(),
+        )
+    }
+
+    /// Plays by the rules of `variant` rather than standard draughts
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.1 = variant;
+        self
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<Piece> {
+        self.0.get(&pos).copied()
+    }
+
+    /// Every move `team` may make. Unless the variant makes them optional, jumps are mandatory,
+    /// so simple moves are only offered when nothing can jump.
+    pub fn legal_moves(&self, team: Team) -> Vec<Move> {
+        let mut jumps = vec![];
+        let mut moves = vec![];
+
+        let mut pieces: Vec<_> = self.0.iter().filter(|(_, p)| p.team == team).collect();
+        // HashMap order is random per process, which would make seeded games unrepeatable
+        pieces.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+        for (pos, piece) in pieces {
+            let pos = *pos;
+
+            for (jumped, hops) in find_jumps(pos, team, piece.king, |p| self.get(p).map(|p| p.team)) {
+                jumps.push(Move { start: pos, jumped, jumps: hops });
+            }
+
+            if jumps.is_empty() || !self.1.forced_captures() {
+                for dest in find_moves(pos, team, piece.king, |p| self.get(p).map(|p| p.team)) {
+                    moves.push(Move { start: pos, jumped: vec![], jumps: vec![dest] });
+                }
+            }
+        }
+
+        if !self.1.forced_captures() {
+            jumps.extend(moves);
+            return jumps;
+        }
+
+        if jumps.is_empty() { moves } else { jumps }
+    }
+
+    /// Whether a move decided on earlier can still be carried out, using the same checks as the game does
+    /// when the foretold red move is played.
+    pub fn can_play(&self, mov: &Move, team: Team) -> bool {
+        matches!(self.get(mov.start), Some(p) if p.team == team)
+        && mov.jumped.iter().all(|pos| self.get(*pos).is_some())
+        && is_on_board(mov.dest())
+        && self.get(mov.dest()).is_none()
+    }
+
+    pub fn apply(&mut self, mov: &Move) {
+        let mut piece = match self.0.remove(&mov.start) {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        for pos in mov.jumped.iter() {
+            self.0.remove(pos);
+        }
+
+        let dest = mov.dest();
+        if (piece.team == Team::Black && dest.y == 0) || (piece.team == Team::Red && dest.y == 7) {
+            piece.king = true;
+        }
+
+        self.0.insert(dest, piece);
+    }
+
+    /// How many pieces `team` is up by, counting kings as two
+    pub fn material(&self, team: Team) -> i32 {
+        self.0.values()
+        .map(|p| {
+            let value = if p.king { 2 } else { 1 };
+            if p.team == team { value } else { -value }
+        })
+        .sum()
+    }
+
+    fn after(&self, mov: &Move) -> Board {
+        let mut board = self.clone();
+        board.apply(mov);
+        board
+    }
+
+    /// Material balance from `team`'s point of view
+    fn evaluate(&self, team: Team, config: &AiConfig) -> f32 {
+        self.0.values()
+        .map(|p| {
+            let value = if p.king { config.king_value } else { 1.0 };
+            if p.team == team { value } else { -value }
+        })
+        .sum()
+    }
+}
+
+fn opponent(team: Team) -> Team {
+    match team {
+        Team::Red => Team::Black,
+        Team::Black => Team::Red,
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty-easy",
+            Difficulty::Normal => "difficulty-normal",
+            Difficulty::Hard => "difficulty-hard",
+        }
+    }
+
+    pub fn config(self) -> AiConfig {
+        match self {
+            // Takes the foretold move at its word and looks no further, which is easy to lead astray
+            Difficulty::Easy => AiConfig {
+                foresight_weight: 1.0,
+                ..Default::default()
+            },
+            Difficulty::Normal => AiConfig {
+                depth: 2,
+                foresight_weight: 0.5,
+                invalidate_bonus: 0.25,
+                ..Default::default()
+            },
+            Difficulty::Hard => AiConfig {
+                depth: 4,
+                foresight_weight: 1.0,
+                invalidate_bonus: 0.5,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AiConfig {
+    /// How many plies to look past the reply to the move being chosen. 0 only looks at the immediate result.
+    pub depth: u32,
+    /// How much to trust the foretold move as the opponent's reply, from 0 (ignore it and assume the best reply)
+    /// to 1 (assume it will be played whenever it still can be)
+    pub foresight_weight: f32,
+    /// Extra score for a reply that invalidates the foretold move
+    pub invalidate_bonus: f32,
+    /// Chance of playing a random legal move instead of the best one
+    pub blunder_chance: f32,
+    /// Worth of a king relative to a regular checker
+    pub king_value: f32,
+}
+
+impl Default for AiConfig {
+    /// Greedy play: grab the most material right now and otherwise move at random
+    fn default() -> Self {
+        AiConfig {
+            depth: 0,
+            foresight_weight: 0.0,
+            invalidate_bonus: 0.0,
+            blunder_chance: 0.0,
+            king_value: 1.0,
+        }
+    }
+}
+
+impl AiConfig {
+    /// Parses either a difficulty name (`easy`, `normal`, `hard`) or a comma separated list of settings,
+    /// e.g. `depth=3,foresight=0.5,invalidate=0.25,blunder=0.1,king=1.5`. Unlisted settings keep their defaults.
+    pub fn parse(text: &str) -> Result<AiConfig, String> {
+        match text {
+            "easy" => return Ok(Difficulty::Easy.config()),
+            "normal" => return Ok(Difficulty::Normal.config()),
+            "hard" => return Ok(Difficulty::Hard.config()),
+            _ => (),
+        }
+
+        let mut config = AiConfig::default();
+
+        for setting in text.split(',').filter(|s| !s.is_empty()) {
+            let (key, value) = setting.split_once('=')
+            .ok_or_else(|| format!("Expected key=value, found `{}`", setting))?;
+
+            match key {
+                "depth" => config.depth = parse_setting(key, value)?,
+                "foresight" => config.foresight_weight = parse_setting(key, value)?,
+                "invalidate" => config.invalidate_bonus = parse_setting(key, value)?,
+                "blunder" => config.blunder_chance = parse_setting(key, value)?,
+                "king" => config.king_value = parse_setting(key, value)?,
+                _ => return Err(format!("Unknown AI setting `{}`", key)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_setting<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid value for `{}`: `{}`", key, value))
+}
+
+/// Plain negamax with alpha-beta pruning, ignoring foresight. Returns the score for `team`, who is to move.
+fn negamax(board: &Board, team: Team, depth: u32, mut alpha: f32, beta: f32, config: &AiConfig) -> f32 {
+    let moves = board.legal_moves(team);
+
+    if moves.is_empty() {
+        return -WIN_SCORE;
+    }
+
+    if depth == 0 {
+        return board.evaluate(team, config);
+    }
+
+    let mut best = f32::NEG_INFINITY;
+    for mov in moves.iter() {
+        let score = -negamax(&board.after(mov), opponent(team), depth - 1, -beta, -alpha, config);
+        best = best.max(score);
+        alpha = alpha.max(score);
+
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Picks uniformly among the highest scoring moves, so equal choices don't always favour the same checker
+fn pick_best(scored: Vec<(Move, f32)>, rng: &mut impl Rng) -> Option<Move> {
+    let best = scored.iter().map(|(_, s)| *s).fold(f32::NEG_INFINITY, f32::max);
+
+    let mut candidates: Vec<_> = scored.into_iter()
+    .filter(|(_, s)| *s >= best - f32::EPSILON)
+    .map(|(m, _)| m)
+    .collect();
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let i = rng.gen_range(0..candidates.len());
+    Some(candidates.swap_remove(i))
+}
+
+fn blunder(moves: &[Move], config: &AiConfig, rng: &mut impl Rng) -> Option<Move> {
+    if config.blunder_chance > 0.0 && rng.gen::<f32>() < config.blunder_chance {
+        moves.choose(rng).cloned()
+    } else {
+        None
+    }
+}
+
+/// Picks a move for whoever is to move normally, with no foretold move involved.
+/// This is what Red uses to correct an invalidated move.
+pub fn choose_move(board: &Board, team: Team, config: &AiConfig, rng: &mut impl Rng) -> Option<Move> {
+    let moves = board.legal_moves(team);
+
+    if let Some(mov) = blunder(&moves, config, rng) {
+        return Some(mov);
+    }
+
+    let scored = moves.into_iter()
+    .map(|m| {
+        let score = -negamax(&board.after(&m), opponent(team), config.depth, f32::NEG_INFINITY, f32::INFINITY, config);
+        (m, score)
+    })
+    .collect();
+
+    pick_best(scored, rng)
+}
+
+/// Picks Black's move knowing the move Red has foretold.
+pub fn choose_black_move(board: &Board, foretold: Option<&Move>, config: &AiConfig, rng: &mut impl Rng) -> Option<Move> {
+    let foretold = match foretold {
+        Some(foretold) => foretold,
+        None => return choose_move(board, Team::Black, config, rng),
+    };
+
+    let moves = board.legal_moves(Team::Black);
+
+    if let Some(mov) = blunder(&moves, config, rng) {
+        return Some(mov);
+    }
+
+    let scored = moves.into_iter()
+    .map(|m| {
+        let after = board.after(&m);
+        let free = -negamax(&after, Team::Red, config.depth, f32::NEG_INFINITY, f32::INFINITY, config);
+
+        let score = if after.can_play(foretold, Team::Red) {
+            let forced = negamax(&after.after(foretold), Team::Black, config.depth, f32::NEG_INFINITY, f32::INFINITY, config);
+            config.foresight_weight * forced + (1.0 - config.foresight_weight) * free
+        } else {
+            free + config.invalidate_bonus
+        };
+
+        (m, score)
+    })
+    .collect();
+
+    pick_best(scored, rng)
+}
+
+/// Picks the move Red foretells before Black replies, assuming Black will exploit it.
+pub fn choose_red_move(board: &Board, config: &AiConfig, rng: &mut impl Rng) -> Option<Move> {
+    let moves = board.legal_moves(Team::Red);
+
+    if let Some(mov) = blunder(&moves, config, rng) {
+        return Some(mov);
+    }
+
+    let replies = board.legal_moves(Team::Black);
+
+    let scored = moves.into_iter()
+    .map(|m| {
+        if replies.is_empty() {
+            return (m, WIN_SCORE);
+        }
+
+        // Black sees this move coming, so assume the reply that hurts it the most
+        let score = replies.iter()
+        .map(|reply| {
+            let after = board.after(reply);
+            let mov = if after.can_play(&m, Team::Red) {
+                Some(m.clone())
+            } else {
+                after.legal_moves(Team::Red).into_iter()
+                .max_by(|a, b| after.after(a).evaluate(Team::Red, config).total_cmp(&after.after(b).evaluate(Team::Red, config)))
+            };
+
+            match mov {
+                Some(mov) => -negamax(&after.after(&mov), Team::Black, config.depth, f32::NEG_INFINITY, f32::INFINITY, config),
+                None => -WIN_SCORE,
+            }
+        })
+        .fold(f32::INFINITY, f32::min);
+
+        (m, score)
+    })
+    .collect();
+
+    pick_best(scored, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(pieces: &[(IVec2, Team, bool)]) -> Board {
+        Board(
+            pieces.iter().map(|(pos, team, king)| (*pos, Piece { team: *team, king: *king })).collect(),
+            Variant::default(),
+        )
+    }
+
+    fn capture(start: IVec2, jumped: IVec2, dest: IVec2) -> Move {
+        Move { start, jumped: vec![jumped], jumps: vec![dest] }
+    }
+
+    fn step(start: IVec2, dest: IVec2) -> Move {
+        Move { start, jumped: vec![], jumps: vec![dest] }
+    }
+
+    #[test]
+    fn captures_are_forced_unless_the_variant_says_otherwise() {
+        let board = board(&[
+            (ivec2(2, 2), Team::Red, false),
+            (ivec2(3, 3), Team::Black, false),
+        ]);
+
+        assert_eq!(board.legal_moves(Team::Red), vec![capture(ivec2(2, 2), ivec2(3, 3), ivec2(4, 4))]);
+
+        let moves = board.with_variant(Variant::OptionalCaptures).legal_moves(Team::Red);
+        assert!(moves.contains(&capture(ivec2(2, 2), ivec2(3, 3), ivec2(4, 4))));
+        assert!(moves.contains(&step(ivec2(2, 2), ivec2(1, 3))));
+    }
+
+    #[test]
+    fn reaching_the_far_row_crowns() {
+        let mut board = board(&[
+            (ivec2(2, 6), Team::Red, false),
+            (ivec2(1, 1), Team::Black, false),
+        ]);
+
+        board.apply(&step(ivec2(2, 6), ivec2(3, 7)));
+        board.apply(&step(ivec2(1, 1), ivec2(0, 0)));
+
+        assert_eq!(board.get(ivec2(3, 7)), Some(Piece { team: Team::Red, king: true }));
+        assert_eq!(board.get(ivec2(0, 0)), Some(Piece { team: Team::Black, king: true }));
+        assert_eq!(board.get(ivec2(2, 6)), None);
+    }
+
+    #[test]
+    fn can_play_checks_the_board_has_not_moved_on() {
+        let board = board(&[
+            (ivec2(2, 2), Team::Red, false),
+            (ivec2(3, 3), Team::Black, false),
+            (ivec2(0, 2), Team::Red, false),
+            (ivec2(1, 3), Team::Black, false),
+        ]);
+
+        assert!(board.can_play(&capture(ivec2(2, 2), ivec2(3, 3), ivec2(4, 4)), Team::Red));
+        // Not Red's checker
+        assert!(!board.can_play(&step(ivec2(3, 3), ivec2(4, 4)), Team::Red));
+        // Nothing left to jump
+        assert!(!board.can_play(&capture(ivec2(2, 2), ivec2(1, 1), ivec2(0, 0)), Team::Red));
+        // Somebody's in the way
+        assert!(!board.can_play(&step(ivec2(0, 2), ivec2(1, 3)), Team::Red));
+    }
+
+    #[test]
+    fn having_no_moves_loses() {
+        let board = board(&[(ivec2(0, 0), Team::Black, false)]);
+
+        assert_eq!(negamax(&board, Team::Black, 2, f32::NEG_INFINITY, f32::INFINITY, &AiConfig::default()), -WIN_SCORE);
+    }
+
+    #[test]
+    fn takes_a_free_capture() {
+        let board = board(&[
+            (ivec2(3, 3), Team::Black, false),
+            (ivec2(7, 7), Team::Black, false),
+            (ivec2(2, 2), Team::Red, false),
+            (ivec2(6, 0), Team::Red, false),
+        ])
+        .with_variant(Variant::OptionalCaptures);
+
+        for difficulty in Difficulty::ALL {
+            let mut rng = StdRng::seed_from_u64(0);
+            let mov = choose_black_move(&board, None, &difficulty.config(), &mut rng);
+
+            assert_eq!(mov, Some(capture(ivec2(3, 3), ivec2(2, 2), ivec2(1, 1))), "{:?}", difficulty);
+        }
+    }
+
+    #[test]
+    fn invalidate_bonus_can_outweigh_a_capture() {
+        // Black can either take a checker, or block where Red has foretold it'll move
+        let board = board(&[
+            (ivec2(3, 3), Team::Black, false),
+            (ivec2(7, 3), Team::Black, false),
+            (ivec2(2, 2), Team::Red, false),
+            (ivec2(5, 1), Team::Red, false),
+        ])
+        .with_variant(Variant::OptionalCaptures);
+        let foretold = step(ivec2(5, 1), ivec2(6, 2));
+
+        let greedy = AiConfig::default();
+        let spiteful = AiConfig { invalidate_bonus: 2.0, ..Default::default() };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            choose_black_move(&board, Some(&foretold), &greedy, &mut rng),
+            Some(capture(ivec2(3, 3), ivec2(2, 2), ivec2(1, 1))),
+        );
+        assert_eq!(
+            choose_black_move(&board, Some(&foretold), &spiteful, &mut rng),
+            Some(step(ivec2(7, 3), ivec2(6, 2))),
+        );
+    }
+
+    #[test]
+    fn every_difficulty_heeds_the_foretold_move() {
+        for difficulty in Difficulty::ALL {
+            assert!(difficulty.config().foresight_weight > 0.0, "{:?}", difficulty);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use super::*;
+use std::collections::HashMap;
+
+/// Seconds after any remark before the opponent says anything else
+const COMMENTARY_COOLDOWN_SECS: f32 = 12.0;
+/// Seconds before the same kind of remark can be made again
+const REMARK_COOLDOWN_SECS: f32 = 45.0;
+/// Seconds a remark stays up before it starts fading
+const REMARK_HOLD_SECS: f32 = 3.0;
+const REMARK_FADE_RATE: f32 = 1.0;
+/// Seconds the player can spend choosing a move before the opponent gets impatient
+const LONG_THINK_SECS: f32 = 25.0;
+/// How many pieces up either side has to get before the opponent mentions it
+const MATERIAL_LEAD: i32 = 3;
+/// Which of `GameRng`'s substreams picks lines
+const COMMENTARY_STREAM: u64 = 1;
+
+/// Asks the opponent to pass comment, if they haven't spoken too recently
+pub struct RemarkEvent(pub Remark);
+
+/// Keeps the opponent from talking over themselves
+#[derive(Default)]
+pub struct Commentary {
+    cooldown: f32,
+    remark_cooldowns: HashMap<Remark, f32>,
+    /// Seconds the current remark has been on screen
+    shown_for: Option<f32>,
+    /// Seconds the player has spent choosing this turn
+    thinking: f32,
+    rng: SubRng,
+}
+
+/// Puts a line for the first remark that's off cooldown on screen, and fades it away again
+pub fn comment(
+    mut commentary: ResMut<Commentary>,
+    mut remarks: EventReader<RemarkEvent>,
+    mut text: ResMut<ScreenText>,
+    runner: Res<DialogueRunner>,
+    scripts: Res<DialogueScripts>,
+    dialogues: Res<Assets<Dialogue>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    game_rng: Res<GameRng>,
+) {
+    // Reading faster shortens how long a remark stays up, but not how often they're made
+    let delta = time.delta_seconds() * settings.text_speed;
+
+    commentary.cooldown -= time.delta_seconds();
+    for cooldown in commentary.remark_cooldowns.values_mut() {
+        *cooldown -= time.delta_seconds();
+    }
+
+    // Scripted dialogue has the screen to itself
+    if runner.is_playing() {
+        commentary.shown_for = None;
+    }
+
+    // The remark stays up for a while once it's been typed out
+    if let (Some(shown_for), true) = (commentary.shown_for, text.is_complete()) {
+        let shown_for = shown_for + delta;
+        commentary.shown_for = Some(shown_for);
+
+        if shown_for > REMARK_HOLD_SECS {
+            text.alpha = (text.alpha - REMARK_FADE_RATE * delta).clamp(0.0, 1.0);
+            if text.alpha <= 0.0 {
+                text.clear();
+                commentary.shown_for = None;
+            }
+        }
+    }
+
+    let lines = match dialogues.get(&scripts.commentary) {
+        Some(script) => &script.remarks,
+        None => return,
+    };
+
+    for remark in remarks.iter() {
+        let cooling = matches!(commentary.remark_cooldowns.get(&remark.0), Some(c) if *c > 0.0);
+        if runner.is_playing() || commentary.cooldown > 0.0 || cooling {
+            continue;
+        }
+
+        // A substream of the game's rng, so lines replay with the seed without changing how the game plays
+        let rng = commentary.rng.get(&game_rng, COMMENTARY_STREAM);
+        let line = match lines.get(&remark.0).and_then(|lines| lines.choose(rng)) {
+            Some(line) => line,
+            None => continue,
+        };
+
+        text.say(&locale.get(line), DEFAULT_TEXT_COLOUR);
+        commentary.cooldown = COMMENTARY_COOLDOWN_SECS;
+        commentary.remark_cooldowns.insert(remark.0, REMARK_COOLDOWN_SECS);
+        commentary.shown_for = Some(0.0);
+    }
+}
+
+pub fn remark_on_correction(mut remarks: EventWriter<RemarkEvent>) {
+    remarks.send(RemarkEvent(Remark::Correction));
+}
+
+/// Comments on captures of more than one checker, and on either side pulling ahead
+pub fn remark_on_moves(
+    log: Res<GameLog>,
+    mut remarks: EventWriter<RemarkEvent>,
+    mut lead: Local<i32>,
+) {
+    if !log.is_changed() {
+        return;
+    }
+
+    let latest = match log.0.last() {
+        Some(latest) => latest,
+        None => {
+            *lead = 0;
+            return;
+        }
+    };
+
+    if latest.mov.jumped.len() > 1 {
+        remarks.send(RemarkEvent(match latest.team {
+            Team::Black => Remark::OwnMultiCapture,
+            Team::Red => Remark::PlayerMultiCapture,
+        }));
+    }
+
+    let mut board = Board::initial();
+    for played in log.0.iter() {
+        board.apply(&played.mov);
+    }
+
+    // Only said as a side first gets far enough ahead, not on every move after
+    let material = board.material(Team::Black);
+    let now_leading = if material >= MATERIAL_LEAD {
+        1
+    } else if material <= -MATERIAL_LEAD {
+        -1
+    } else {
+        0
+    };
+
+    if now_leading != *lead {
+        match now_leading {
+            1 => remarks.send(RemarkEvent(Remark::Ahead)),
+            -1 => remarks.send(RemarkEvent(Remark::Behind)),
+            _ => {},
+        }
+        *lead = now_leading;
+    }
+}
+
+/// Comments on a checker being crowned. Has to run before `make_kings` takes the `Kingify` away.
+pub fn remark_on_kings(
+    checkers: Query<(&Checker, &Kingify), Added<Kingify>>,
+    mut remarks: EventWriter<RemarkEvent>,
+) {
+    for (checker, kingify) in checkers.iter() {
+        if kingify.0 && !checker.king {
+            remarks.send(RemarkEvent(match checker.team {
+                Team::Black => Remark::OwnKing,
+                Team::Red => Remark::PlayerKing,
+            }));
+        }
+    }
+}
+
+/// Starts timing the player's turn. Only on entering a turn, so pausing partway through doesn't start it over.
+pub fn start_thinking(mut commentary: ResMut<Commentary>) {
+    commentary.thinking = 0.0;
+}
+
+/// Comments once a turn if the player is taking too long to choose
+pub fn remark_on_long_thinks(
+    state: Res<State<GameState>>,
+    mut remarks: EventWriter<RemarkEvent>,
+    time: Res<Time>,
+    mut commentary: ResMut<Commentary>,
+) {
+    if !matches!(state.current(), GameState::BlackForesight | GameState::RedCorrection) {
+        return;
+    }
+
+    let before = commentary.thinking;
+    commentary.thinking += time.delta_seconds();
+
+    if before < LONG_THINK_SECS && commentary.thinking >= LONG_THINK_SECS {
+        remarks.send(RemarkEvent(Remark::LongThink));
+    }
+}
@@ -0,0 +1,170 @@
+use super::*;
+
+/// Seconds between steps while the stick is held over
+const STICK_REPEAT_SECS: f32 = 0.2;
+
+/// A square picked out for choosing without the mouse. Only shown once a controller has moved it.
+pub struct BoardCursor {
+    pub pos: IVec2,
+    pub visible: bool,
+}
+
+impl Default for BoardCursor {
+    fn default() -> Self {
+        // In front of the player, on their first row that can move
+        BoardCursor { pos: ivec2(2, 2), visible: false }
+    }
+}
+
+impl BoardCursor {
+    pub fn step(&mut self, step: IVec2) {
+        self.pos = (self.pos + step).clamp(IVec2::ZERO, ivec2(7, 7));
+        self.visible = true;
+    }
+}
+
+/// Moves the cursor a square at a time with the d-pad or left stick, and chooses its square with the south button
+pub fn gamepad_cursor(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut cursor: ResMut<BoardCursor>,
+    mut squares: EventWriter<ChooseSquareEvent>,
+    time: Res<Time>,
+    mut repeat: Local<f32>,
+) {
+    for gamepad in gamepads.iter() {
+        let pressed = |button| buttons.just_pressed(GamepadButton(*gamepad, button));
+        let axis = |axis| axes.get(GamepadAxis(*gamepad, axis)).unwrap_or(0.0);
+
+        let mut step = IVec2::ZERO;
+
+        // Up is away from the player
+        if pressed(GamepadButtonType::DPadUp) { step.y += 1; }
+        if pressed(GamepadButtonType::DPadDown) { step.y -= 1; }
+        if pressed(GamepadButtonType::DPadLeft) { step.x -= 1; }
+        if pressed(GamepadButtonType::DPadRight) { step.x += 1; }
+
+        let stick = vec2(axis(GamepadAxisType::LeftStickX), axis(GamepadAxisType::LeftStickY));
+        let stick = ivec2(
+            if stick.x.abs() > 0.5 { stick.x.signum() as i32 } else { 0 },
+            if stick.y.abs() > 0.5 { stick.y.signum() as i32 } else { 0 },
+        );
+
+        if stick != IVec2::ZERO {
+            *repeat -= time.delta_seconds();
+            if *repeat <= 0.0 {
+                step += stick;
+                *repeat = STICK_REPEAT_SECS;
+            }
+        } else {
+            *repeat = 0.0;
+        }
+
+        if step != IVec2::ZERO {
+            cursor.step(step);
+        }
+
+        if pressed(GamepadButtonType::South) {
+            cursor.visible = true;
+            squares.send(ChooseSquareEvent(cursor.pos));
+        }
+    }
+}
+
+/// What's been typed of a move so far
+#[derive(Default)]
+pub struct MoveEntry(pub String);
+
+/// Arrow keys move the cursor and Enter chooses its square. Typing a move in draughts notation and pressing Enter
+/// plays it instead, and Escape throws away what's been typed rather than pausing.
+pub fn keyboard_cursor(
+    mut input: ResMut<Input<KeyCode>>,
+    mut chars: EventReader<ReceivedCharacter>,
+    mut entry: ResMut<MoveEntry>,
+    mut cursor: ResMut<BoardCursor>,
+    mut squares: EventWriter<ChooseSquareEvent>,
+    mut typed: EventWriter<TypedMoveEvent>,
+) {
+    for c in chars.iter() {
+        if c.char.is_ascii_digit() || matches!(c.char, '-' | 'x' | 'X') {
+            entry.0.push(c.char);
+        }
+    }
+
+    if input.just_pressed(KeyCode::Back) {
+        entry.0.pop();
+    }
+
+    if !entry.0.is_empty() && input.clear_just_pressed(KeyCode::Escape) {
+        entry.0.clear();
+    }
+
+    let mut step = IVec2::ZERO;
+
+    if input.just_pressed(KeyCode::Up) { step.y += 1; }
+    if input.just_pressed(KeyCode::Down) { step.y -= 1; }
+    if input.just_pressed(KeyCode::Left) { step.x -= 1; }
+    if input.just_pressed(KeyCode::Right) { step.x += 1; }
+
+    if step != IVec2::ZERO {
+        cursor.step(step);
+    }
+
+    if input.just_pressed(KeyCode::Return) || input.just_pressed(KeyCode::NumpadEnter) {
+        if entry.0.is_empty() {
+            cursor.visible = true;
+            squares.send(ChooseSquareEvent(cursor.pos));
+        } else {
+            typed.send(TypedMoveEvent(std::mem::take(&mut entry.0)));
+        }
+    }
+}
+
+pub fn move_entry_display(
+    mut ctx: ResMut<EguiContext>,
+    entry: Res<MoveEntry>,
+) {
+    if entry.0.is_empty() {
+        return;
+    }
+
+    egui::Area::new("MoveEntry")
+    .anchor(egui::Align2::CENTER_BOTTOM, (0., -16.))
+    .movable(false)
+    .interactable(false)
+    .show(ctx.ctx_mut(), |ui| {
+        ui.heading(format!("{}_", entry.0));
+    });
+}
+
+#[derive(Component)]
+pub struct CursorMarker;
+
+pub fn show_board_cursor(
+    mut commands: Commands,
+    cursor: Res<BoardCursor>,
+    state: Res<State<GameState>>,
+    mut markers: Query<(&mut Transform, &mut Visibility), With<CursorMarker>>,
+    assets: Res<HighlightAssets>,
+) {
+    let pos = get_checkboard_pos(cursor.pos) + vec3(0.0, 0.003, 0.0);
+    let visible = cursor.visible && state.current().is_playing();
+
+    match markers.get_single_mut() {
+        Ok((mut tf, mut visibility)) => {
+            tf.translation = pos;
+            visibility.is_visible = visible;
+        },
+        Err(_) => {
+            commands.spawn_bundle(PbrBundle {
+                mesh: assets.square.clone(),
+                material: assets.cursor.clone(),
+                transform: Transform::from_translation(pos),
+                visibility: Visibility { is_visible: visible },
+                ..Default::default()
+            })
+            .insert(CursorMarker);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use super::*;
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The colour the opponent speaks in, used by any line that doesn't give its own
+pub const DEFAULT_TEXT_COLOUR: [f32; 3] = [0.5, 0.5, 0.5];
+
+/// A script of lines and cues, loaded from a `.ron` file under `assets/dialogue`. Lines are message ids, looked up
+/// in the player's language as they're shown.
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "6b0f3a52-9d1e-4c7a-8e24-3f5b7d9c1a80"]
+pub struct Dialogue {
+    #[serde(default)]
+    pub steps: Vec<DialogueStep>,
+    /// Lines to pick from when something happens during play, as message ids
+    #[serde(default)]
+    pub remarks: HashMap<Remark, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct DialogueStep {
+    /// Seconds to wait after the previous step before this one
+    #[serde(default)]
+    pub wait: f32,
+    pub action: DialogueAction,
+}
+
+#[derive(Deserialize)]
+pub enum DialogueAction {
+    /// Replaces whatever is on screen
+    Say {
+        line: String,
+        #[serde(default)]
+        colour: Option<[f32; 3]>,
+    },
+    /// Adds to the end of the last line, in the same colour
+    Continue(String),
+    /// Adds a line below what's on screen, in the same colour
+    Line(String),
+    Clear,
+    /// Fades the text out at this much opacity a second, holding the script until it's gone
+    FadeOut(f32),
+    Trigger(DialogueTrigger),
+}
+
+/// Something for the game to do at a point in the script
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub enum DialogueTrigger {
+    /// Puts every checker back without leaving the current state
+    ResetBoard,
+    /// Starts play with Black's foresight
+    Play,
+    /// Sends the camera tumbling off the table
+    CameraFall,
+    Exit,
+}
+
+/// Something in play the opponent might comment on
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub enum Remark {
+    /// Black has made the foretold move impossible
+    Correction,
+    OwnMultiCapture,
+    PlayerMultiCapture,
+    OwnKing,
+    PlayerKing,
+    /// Black has pulled ahead on material
+    Ahead,
+    /// The player has pulled ahead on material
+    Behind,
+    /// The player is taking a long time to choose
+    LongThink,
+}
+
+#[derive(Default)]
+pub struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let dialogue: Dialogue = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(dialogue));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Every script the game plays
+pub struct DialogueScripts {
+    pub intro: Handle<Dialogue>,
+    pub red_wins: Handle<Dialogue>,
+    pub black_wins: Handle<Dialogue>,
+    pub commentary: Handle<Dialogue>,
+}
+
+pub fn load_dialogue(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(DialogueScripts {
+        intro: server.load("dialogue/intro.ron"),
+        red_wins: server.load("dialogue/red_wins.ron"),
+        black_wins: server.load("dialogue/black_wins.ron"),
+        commentary: server.load("dialogue/commentary.ron"),
+    });
+}
+
+/// The script being played, and how far through it is
+#[derive(Default)]
+pub struct DialogueRunner {
+    dialogue: Option<Handle<Dialogue>>,
+    step: usize,
+    waited: f32,
+    /// Cuts the current wait short
+    skipping: bool,
+}
+
+impl DialogueRunner {
+    /// Plays a script from the top on a clean screen, dropping whatever was playing before
+    pub fn start(&mut self, dialogue: Handle<Dialogue>, text: &mut ScreenText) {
+        *self = DialogueRunner { dialogue: Some(dialogue), step: 0, waited: 0.0, skipping: false };
+        text.reset();
+    }
+
+    pub fn stop(&mut self) {
+        self.dialogue = None;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.dialogue.is_some()
+    }
+
+    /// Moves on to the next step without waiting
+    pub fn skip_wait(&mut self) {
+        self.skipping = true;
+    }
+}
+
+/// Steps through the playing script, showing its lines through `ScreenText` and sending its triggers
+pub fn run_dialogue(
+    mut runner: ResMut<DialogueRunner>,
+    dialogues: Res<Assets<Dialogue>>,
+    mut text: ResMut<ScreenText>,
+    mut triggers: EventWriter<DialogueTrigger>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
+    // Waits for the script to finish loading, too
+    let dialogue = match runner.dialogue.as_ref().and_then(|d| dialogues.get(d)) {
+        Some(dialogue) => dialogue,
+        None => return,
+    };
+
+    // The wait before the next step starts once the last line has been typed out
+    if !text.is_complete() {
+        return;
+    }
+
+    let delta = time.delta_seconds() * settings.text_speed;
+    let mut skipping = std::mem::take(&mut runner.skipping);
+    runner.waited += delta;
+
+    // A slow frame can cover several steps
+    while let Some(step) = dialogue.steps.get(runner.step) {
+        if skipping {
+            runner.waited = runner.waited.max(step.wait);
+        }
+
+        if runner.waited < step.wait || !text.is_complete() {
+            break;
+        }
+
+        match &step.action {
+            DialogueAction::Say { line, colour } => text.say(&locale.get(line), colour.unwrap_or(DEFAULT_TEXT_COLOUR)),
+            DialogueAction::Continue(line) => text.continue_line(&locale.get(line)),
+            DialogueAction::Line(line) => text.add_line(&locale.get(line)),
+            DialogueAction::Clear => text.clear(),
+            DialogueAction::FadeOut(rate) => {
+                text.alpha = match skipping {
+                    true => 0.0,
+                    false => (text.alpha - rate * delta).clamp(0.0, 1.0),
+                };
+                if text.alpha > 0.0 {
+                    break;
+                }
+                // The fade itself doesn't count towards the next step's wait
+                runner.waited = step.wait;
+            },
+            DialogueAction::Trigger(trigger) => triggers.send(*trigger),
+        }
+
+        runner.waited -= step.wait;
+        runner.step += 1;
+        skipping = false;
+    }
+
+    if runner.step >= dialogue.steps.len() {
+        runner.stop();
+    }
+}
+
+/// Space or a click finishes typing the current line, or if it's already finished, moves the script along
+pub fn advance_dialogue(
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut text: ResMut<ScreenText>,
+    mut runner: ResMut<DialogueRunner>,
+) {
+    if !runner.is_playing() || !(keys.just_pressed(KeyCode::Space) || mouse.just_pressed(MouseButton::Left)) {
+        return;
+    }
+
+    match text.is_complete() {
+        true => runner.skip_wait(),
+        false => text.complete(),
+    }
+}
+
+/// Carries out the cues the scripts give
+pub fn act_on_dialogue(
+    mut commands: Commands,
+    mut triggers: EventReader<DialogueTrigger>,
+    mut state: ResMut<State<GameState>>,
+    mut restarts: EventWriter<RestartEvent>,
+    mut exit: EventWriter<bevy::app::AppExit>,
+    camera: Query<(Entity, &Transform), With<PlayerCamera>>,
+) {
+    for trigger in triggers.iter() {
+        match trigger {
+            DialogueTrigger::ResetBoard => restarts.send(RestartEvent(None)),
+            // Escape can skip to play on the same frame, which is just as good
+            DialogueTrigger::Play => { state.try_set(GameState::BlackForesight); },
+            DialogueTrigger::CameraFall => {
+                if let Ok((cam, tf)) = camera.get_single() {
+                    // Any easing left over from leaning back would fight the fall
+                    commands.entity(cam)
+                    .remove::<Animation>()
+                    .remove::<LerpToTarget>()
+                    .remove::<SlerpToTarget>()
+                    // Up and away over the table before falling into the dark
+                    .insert(QuadJump::bezier(
+                        tf.translation,
+                        tf.translation + vec3(0.0, 10.0, -5.0),
+                        tf.translation + vec3(0.0, 5.0, -12.0),
+                        vec3(0.0, -15.0, -20.0),
+                    ).over(5.0))
+                    .insert(StareAt(vec3(0.0, 0.25, 0.0)));
+                }
+            },
+            DialogueTrigger::Exit => exit.send(bevy::app::AppExit),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use super::*;
+use bevy::pbr::AlphaMode;
+use std::collections::HashSet;
+
+/// Materials and meshes for everything drawn on the board to show what can happen
+pub struct HighlightAssets {
+    pub place: Handle<StandardMaterial>,
+    pub valid: Handle<StandardMaterial>,
+    pub captured: Handle<StandardMaterial>,
+    pub path: Handle<StandardMaterial>,
+    pub outline: Handle<StandardMaterial>,
+    pub last_black: Handle<StandardMaterial>,
+    pub last_red: Handle<StandardMaterial>,
+    pub cursor: Handle<StandardMaterial>,
+    pub dot: Handle<Mesh>,
+    pub ring: Handle<Mesh>,
+    pub puck: Handle<Mesh>,
+    pub square: Handle<Mesh>,
+}
+
+/// Anything spawned to show the selected checker's moves, cleared whenever they change
+#[derive(Component)]
+pub struct MoveMarker;
+
+fn highlight_material(color: Color) -> StandardMaterial {
+    StandardMaterial {
+        base_color: color,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..Default::default()
+    }
+}
+
+pub fn set_up_highlights(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(HighlightAssets {
+        // Places have to stay visible to be picked, so unhighlighted ones are see-through instead
+        place: materials.add(highlight_material(Color::rgba(0.0, 0.0, 0.0, 0.0))),
+        valid: materials.add(highlight_material(Color::rgba(1.0, 0.85, 0.4, 0.4))),
+        captured: materials.add(highlight_material(Color::rgba(0.9, 0.1, 0.1, 0.45))),
+        path: materials.add(highlight_material(Color::rgba(1.0, 0.85, 0.4, 0.8))),
+        outline: materials.add(highlight_material(Color::rgb(1.0, 0.85, 0.4))),
+        last_black: materials.add(highlight_material(Color::rgba(0.55, 0.65, 0.9, 0.35))),
+        last_red: materials.add(highlight_material(Color::rgba(0.9, 0.3, 0.25, 0.35))),
+        cursor: materials.add(highlight_material(Color::rgba(1.0, 1.0, 1.0, 0.3))),
+        dot: meshes.add(shape::Icosphere { radius: 0.004, subdivisions: 1 }.into()),
+        ring: meshes.add(shape::Torus {
+            radius: 0.029,
+            ring_radius: 0.002,
+            subdivisions_segments: 32,
+            subdivisions_sides: 8,
+        }.into()),
+        puck: meshes.add(shape::UVSphere { radius: 0.025, sectors: 24, stacks: 12 }.into()),
+        square: meshes.add(shape::Plane { size: 0.055 }.into()),
+    });
+}
+
+/// Points along a hop from one place to another, arcing up over the checker being jumped
+pub fn arc_points(from: IVec2, to: IVec2, height: f32, count: usize) -> impl Iterator<Item = Vec3> {
+    let (from, to) = (get_checkboard_pos(from), get_checkboard_pos(to));
+
+    (1..count).map(move |i| {
+        let t = i as f32 / count as f32;
+        from.lerp(to, t) + Vec3::Y * (4.0 * height * t * (1.0 - t))
+    })
+}
+
+/// Lets through whatever the run criteria before it did, and anything on a frame where a place's moves have changed
+pub fn or_if_places_changed(In(should_run): In<ShouldRun>, changed: Query<(), Changed<CheckerPlace>>) -> ShouldRun {
+    match changed.is_empty() {
+        true => should_run,
+        false => ShouldRun::Yes,
+    }
+}
+
+/// Lights up where the selected checker can go, the path of its jumps and what they'd capture, and outlines it.
+/// Runs whenever the state, the selection or the places' moves change, so the highlights are cleared as soon as the
+/// player stops choosing a move.
+pub fn highlight_moves(
+    mut commands: Commands,
+    selected: Res<SelectedChecker>,
+    state: Res<State<GameState>>,
+    mut places: Query<(&CheckerPlace, &mut Handle<StandardMaterial>)>,
+    checkers: Query<&Checker>,
+    markers: Query<Entity, With<MoveMarker>>,
+    assets: Res<HighlightAssets>,
+) {
+    for marker in markers.iter() {
+        commands.entity(marker).despawn_recursive();
+    }
+
+    let choosing = matches!(state.current(), GameState::BlackForesight | GameState::RedCorrection);
+    let checker = match selected.0 {
+        Some(id) if choosing => checkers.get(id).ok().map(|c| (id, c)),
+        _ => None,
+    };
+
+    let mut captured = HashSet::new();
+
+    if let Some((id, checker)) = checker {
+        commands.entity(id).with_children(|b| {
+            b.spawn_bundle(PbrBundle {
+                mesh: assets.ring.clone(),
+                material: assets.outline.clone(),
+                transform: Transform::from_xyz(0.0, 0.004, 0.0),
+                ..Default::default()
+            })
+            .insert(MoveMarker);
+        });
+
+        for (place, _) in places.iter_mut().filter(|(p, _)| p.valid && !p.jumped.is_empty()) {
+            captured.extend(place.jumped.iter().copied());
+
+            let hops: Vec<_> = std::iter::once(checker.pos).chain(place.jumps.iter().copied()).collect();
+            for hop in hops.windows(2) {
+                for point in arc_points(hop[0], hop[1], 0.03, 8) {
+                    commands.spawn_bundle(PbrBundle {
+                        mesh: assets.dot.clone(),
+                        material: assets.path.clone(),
+                        transform: Transform::from_translation(point),
+                        ..Default::default()
+                    })
+                    .insert(MoveMarker);
+                }
+            }
+        }
+    }
+
+    for (place, mut material) in places.iter_mut() {
+        *material = if checker.is_none() {
+            assets.place.clone()
+        } else if place.valid {
+            assets.valid.clone()
+        } else if captured.contains(&place.pos) {
+            assets.captured.clone()
+        } else {
+            assets.place.clone()
+        };
+    }
+}
+
+const GHOST_FADE_SECS: f32 = 0.6;
+
+/// Part of the see-through preview of the foretold move
+#[derive(Component)]
+pub struct ForetoldGhost;
+
+/// Sends a piece of the ghost flying off as it fades, once Black has invalidated the foretold move
+#[derive(Component)]
+pub struct GhostShatter {
+    elapsed: f32,
+    velocity: Vec3,
+}
+
+/// Shows where the foretold checker will end up, and the hops it'll take to get there, while Black replies
+pub fn spawn_foretold_ghost(
+    mut commands: Commands,
+    red_move: Res<RedMove>,
+    assets: Res<HighlightAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if red_move.jumps.is_empty() {
+        return;
+    }
+
+    // Each ghost gets its own material so it can fade without touching the next one
+    let material = materials.add(highlight_material(Color::rgba(0.8, 0.1, 0.1, 0.4)));
+    let dest = get_checkboard_pos(*red_move.jumps.last().unwrap());
+
+    commands.spawn_bundle(PbrBundle {
+        mesh: assets.puck.clone(),
+        material: material.clone(),
+        transform: Transform::from_translation(dest + vec3(0.0, 0.005, 0.0)).with_scale(vec3(1.0, 0.3, 1.0)),
+        ..Default::default()
+    })
+    .insert(ForetoldGhost);
+
+    if red_move.jumped.is_empty() {
+        return;
+    }
+
+    let hops: Vec<_> = std::iter::once(red_move.start).chain(red_move.jumps.iter().copied()).collect();
+    for hop in hops.windows(2) {
+        for point in arc_points(hop[0], hop[1], 0.03, 8) {
+            commands.spawn_bundle(PbrBundle {
+                mesh: assets.dot.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(point),
+                ..Default::default()
+            })
+            .insert(ForetoldGhost);
+        }
+    }
+}
+
+/// Breaks the ghost apart when the foretold move turns out to be impossible
+pub fn shatter_foretold_ghost(
+    mut commands: Commands,
+    ghosts: Query<(Entity, &Transform), With<ForetoldGhost>>,
+    red_move: Res<RedMove>,
+) {
+    let dest = match red_move.jumps.last() {
+        Some(dest) => get_checkboard_pos(*dest),
+        None => return,
+    };
+
+    for (ghost, tf) in ghosts.iter() {
+        let outwards = (tf.translation - dest).normalize_or_zero();
+
+        commands.entity(ghost).insert(GhostShatter {
+            elapsed: 0.0,
+            velocity: outwards * 0.1 + Vec3::Y * 0.05,
+        });
+    }
+}
+
+/// Clears away the ghost once the foretold move has been played, or the game is over
+pub fn clear_foretold_ghost(
+    mut commands: Commands,
+    ghosts: Query<Entity, (With<ForetoldGhost>, Without<GhostShatter>)>,
+) {
+    for ghost in ghosts.iter() {
+        commands.entity(ghost).despawn();
+    }
+}
+
+pub fn shatter_ghosts(
+    mut commands: Commands,
+    mut ghosts: Query<(Entity, &mut Transform, &mut GhostShatter, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (ghost, mut tf, mut shatter, material) in ghosts.iter_mut() {
+        shatter.elapsed += time.delta_seconds();
+        tf.translation += shatter.velocity * time.delta_seconds();
+
+        let left = 1.0 - (shatter.elapsed / GHOST_FADE_SECS);
+        if left <= 0.0 {
+            commands.entity(ghost).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color.set_a(0.4 * left);
+        }
+    }
+}
+
+const CAPTURE_FLASH_SECS: f32 = 1.5;
+
+/// Marks a square that a side's most recent move started or ended on
+#[derive(Component)]
+pub struct LastMoveMarker(pub Team);
+
+/// Briefly lights up a square a checker was just captured on
+#[derive(Component)]
+pub struct CaptureFlash {
+    elapsed: f32,
+}
+
+/// Keeps the latest move of each side marked on the board, and flashes whatever it captured
+pub fn show_last_moves(
+    mut commands: Commands,
+    log: Res<GameLog>,
+    markers: Query<(Entity, &LastMoveMarker)>,
+    assets: Res<HighlightAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !log.is_changed() {
+        return;
+    }
+
+    // A new game clears the log, and with it every marker
+    let latest = match log.0.last() {
+        Some(latest) => latest,
+        None => {
+            for (marker, _) in markers.iter() {
+                commands.entity(marker).despawn();
+            }
+            return;
+        }
+    };
+
+    for (marker, _) in markers.iter().filter(|(_, m)| m.0 == latest.team) {
+        commands.entity(marker).despawn();
+    }
+
+    let material = match latest.team {
+        Team::Black => assets.last_black.clone(),
+        Team::Red => assets.last_red.clone(),
+    };
+    // Sits just above the places, so it still shows through their highlights
+    let lift = vec3(0.0, 0.001, 0.0);
+
+    for (pos, scale) in [(latest.mov.start, 0.6), (latest.mov.dest(), 1.0)] {
+        commands.spawn_bundle(PbrBundle {
+            mesh: assets.square.clone(),
+            material: material.clone(),
+            transform: Transform::from_translation(get_checkboard_pos(pos) + lift).with_scale(Vec3::splat(scale)),
+            ..Default::default()
+        })
+        .insert(LastMoveMarker(latest.team));
+    }
+
+    if latest.mov.jumped.is_empty() {
+        return;
+    }
+
+    let flash = materials.add(highlight_material(Color::rgba(1.0, 0.2, 0.1, 0.6)));
+    for pos in latest.mov.jumped.iter() {
+        commands.spawn_bundle(PbrBundle {
+            mesh: assets.square.clone(),
+            material: flash.clone(),
+            transform: Transform::from_translation(get_checkboard_pos(*pos) + lift * 2.0),
+            ..Default::default()
+        })
+        .insert(CaptureFlash { elapsed: 0.0 });
+    }
+}
+
+pub fn fade_capture_flashes(
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut CaptureFlash, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (flash, mut fade, material) in flashes.iter_mut() {
+        fade.elapsed += time.delta_seconds();
+
+        let left = 1.0 - (fade.elapsed / CAPTURE_FLASH_SECS);
+        if left <= 0.0 {
+            commands.entity(flash).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color.set_a(0.6 * left);
+        }
+    }
+}
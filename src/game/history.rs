@@ -0,0 +1,125 @@
+use super::*;
+
+const BLACK_MOVE_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 200, 200);
+const FORETOLD_MOVE_COLOR: egui::Color32 = egui::Color32::from_rgb(210, 70, 60);
+const CORRECTION_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 150, 60);
+
+/// The move history entry whose position is being previewed. `None` follows the game as it's played.
+#[derive(Default)]
+pub struct HistoryPreview(pub Option<usize>);
+
+/// Lists every move of the game so far down the side of the screen
+pub fn move_history(
+    mut ctx: ResMut<EguiContext>,
+    log: Res<GameLog>,
+    red_move: Res<RedMove>,
+    state: Res<State<GameState>>,
+    mut preview: ResMut<HistoryPreview>,
+    locale: Res<Locale>,
+) {
+    // The log is cleared for a new game, taking the previewed move with it
+    if matches!(preview.0, Some(i) if i >= log.0.len()) {
+        preview.0 = None;
+    }
+
+    let latest = log.0.len().checked_sub(1);
+    let current = preview.0.or(latest);
+
+    egui::SidePanel::right("MoveHistory")
+    .resizable(false)
+    .show(ctx.ctx_mut(), |ui| {
+        ui.label(egui::RichText::new(locale.get("history-moves")).strong());
+
+        egui::ScrollArea::vertical()
+        .max_height(ui.available_height() / 2.)
+        .show(ui, |ui| {
+            let mut number = 0;
+
+            for (i, played) in log.0.iter().enumerate() {
+                if played.team == Team::Black {
+                    number += 1;
+                }
+
+                let entry = match (played.team, &played.replaced) {
+                    (Team::Black, _) => egui::RichText::new(format!("{}. {}", number, move_notation(&played.mov)))
+                    .color(BLACK_MOVE_COLOR),
+                    (Team::Red, None) => egui::RichText::new(format!("{}... {}", number, move_notation(&played.mov)))
+                    .color(FORETOLD_MOVE_COLOR),
+                    (Team::Red, Some(_)) => egui::RichText::new(format!("{}... {}", number, move_notation(&played.mov)))
+                    .color(CORRECTION_COLOR),
+                };
+
+                ui.horizontal(|ui| {
+                    let response = ui.selectable_label(Some(i) == current, entry);
+
+                    if let Some(replaced) = &played.replaced {
+                        ui.label(egui::RichText::new(move_notation(replaced)).strikethrough().weak())
+                        .on_hover_text(locale.get("history-invalidated"));
+                    }
+
+                    if response.clicked() {
+                        preview.0 = if Some(i) == latest { None } else { Some(i) };
+                    }
+                });
+            }
+
+            // Declared, but not played until Black has replied
+            if matches!(state.current(), GameState::Black | GameState::Red) && !red_move.jumps.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!("{}... {}", number, move_notation(&red_move.as_move())))
+                    .color(FORETOLD_MOVE_COLOR)
+                    .italics()
+                )
+                .on_hover_text(locale.get("history-foretold"));
+            }
+        });
+
+        if let Some(i) = preview.0 {
+            ui.separator();
+
+            let mut board = Board::initial();
+            for played in log.0[..=i].iter() {
+                board.apply(&played.mov);
+            }
+
+            board_diagram(ui, &board);
+
+            if ui.button(locale.get("history-back")).clicked() {
+                preview.0 = None;
+            }
+        }
+    });
+}
+
+/// Draws a flat board as the player sees it, with Red's side at the bottom
+fn board_diagram(ui: &mut egui::Ui, board: &Board) {
+    let size = ui.available_width().min(240.);
+    let tile = size / 8.;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter();
+
+    for y in 0..8 {
+        for x in 0..8 {
+            let min = rect.left_top() + egui::vec2(tile * x as f32, tile * (7 - y) as f32);
+            let square = egui::Rect::from_min_size(min, egui::vec2(tile, tile));
+
+            let color = match (x + y) % 2 {
+                0 => egui::Color32::from_rgb(60, 40, 30),
+                _ => egui::Color32::from_rgb(200, 180, 150),
+            };
+            painter.rect_filled(square, 0., color);
+
+            if let Some(piece) = board.get(ivec2(x, y)) {
+                let color = match piece.team {
+                    Team::Red => egui::Color32::from_rgb(180, 40, 40),
+                    Team::Black => egui::Color32::from_rgb(20, 20, 20),
+                };
+                painter.circle_filled(square.center(), tile * 0.4, color);
+
+                if piece.king {
+                    painter.circle_stroke(square.center(), tile * 0.25, egui::Stroke::new(2., egui::Color32::GOLD));
+                }
+            }
+        }
+    }
+}
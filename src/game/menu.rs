@@ -0,0 +1,122 @@
+use super::*;
+use super::ai::Difficulty;
+
+/// The turn a game left for the menu was on. The game stays underneath the menu until Continue pops back to it.
+pub struct ContinueState(pub Option<GameState>);
+
+/// Which screen of the menu is showing
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MenuPage {
+    Main,
+    Settings,
+}
+
+/// Uses the bundled GideonRoman for all of egui's text
+pub fn set_up_fonts(mut ctx: ResMut<EguiContext>) {
+    let mut fonts = egui::FontDefinitions::default();
+
+    fonts.font_data.insert(
+        "GideonRoman".to_string(),
+        egui::FontData::from_static(include_bytes!("../../assets/fonts/GideonRoman-Regular.ttf")),
+    );
+    fonts.fonts_for_family.get_mut(&egui::FontFamily::Proportional).unwrap()
+    .insert(0, "GideonRoman".to_string());
+
+    fonts.family_and_size.insert(egui::TextStyle::Heading, (egui::FontFamily::Proportional, 40.));
+    fonts.family_and_size.insert(egui::TextStyle::Button, (egui::FontFamily::Proportional, 24.));
+    fonts.family_and_size.insert(egui::TextStyle::Body, (egui::FontFamily::Proportional, 20.));
+
+    ctx.ctx_mut().set_fonts(fonts);
+}
+
+pub fn main_menu(
+    mut ctx: ResMut<EguiContext>,
+    mut state: ResMut<State<GameState>>,
+    mut continuing: ResMut<ContinueState>,
+    mut variant: ResMut<Variant>,
+    mut difficulty: ResMut<Difficulty>,
+    mut ai: ResMut<AiConfig>,
+    mut page: ResMut<MenuPage>,
+    mut settings: ResMut<Settings>,
+    mut restarts: EventWriter<RestartEvent>,
+    mut exit: EventWriter<bevy::app::AppExit>,
+    locale: Res<Locale>,
+) {
+    let chosen_difficulty = *difficulty;
+    // Edited on a copy so that the settings only count as changed when one of them actually is
+    let mut edited = settings.clone();
+
+    egui::Area::new("MainMenu")
+    .anchor(egui::Align2::LEFT_CENTER, (64., 0.))
+    .movable(false)
+    .show(ctx.ctx_mut(), |ui| {
+        egui::Frame::none()
+        .fill(egui::Color32::from_rgba_unmultiplied(21, 22, 19, 220))
+        .margin((32., 24.))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new("FORETOLD")
+                .color(egui::Color32::from_rgb(244, 244, 237))
+                .heading()
+            );
+            ui.add_space(16.);
+
+            if *page == MenuPage::Settings {
+                if settings_ui(ui, &mut edited, &locale) {
+                    edited.save();
+                    *page = MenuPage::Main;
+                }
+                return;
+            }
+
+            if ui.button(locale.get("menu-new-game")).clicked() {
+                match continuing.0.take() {
+                    Some(_) => restarts.send(RestartEvent(Some(GameState::Intro))),
+                    None => { state.try_set(GameState::Intro); },
+                }
+            }
+
+            if ui.add_enabled(continuing.0.is_some(), egui::Button::new(locale.get("menu-continue"))).clicked()
+            && state.try_pop() {
+                continuing.0 = None;
+            }
+
+            // The rules can't change halfway through a game
+            ui.scope(|ui| {
+                ui.set_enabled(continuing.0.is_none());
+
+                egui::ComboBox::from_label(locale.get("menu-variant"))
+                .selected_text(locale.get(variant.message_id()))
+                .show_ui(ui, |ui| {
+                    for v in Variant::ALL {
+                        ui.selectable_value(&mut *variant, v, locale.get(v.message_id()));
+                    }
+                });
+            });
+
+            egui::ComboBox::from_label(locale.get("menu-difficulty"))
+            .selected_text(locale.get(difficulty.message_id()))
+            .show_ui(ui, |ui| {
+                for d in Difficulty::ALL {
+                    ui.selectable_value(&mut *difficulty, d, locale.get(d.message_id()));
+                }
+            });
+
+            if ui.button(locale.get("menu-settings")).clicked() {
+                *page = MenuPage::Settings;
+            }
+
+            if ui.button(locale.get("menu-quit")).clicked() {
+                exit.send(bevy::app::AppExit);
+            }
+        });
+    });
+
+    if *difficulty != chosen_difficulty {
+        *ai = difficulty.config();
+    }
+
+    if edited != *settings {
+        *settings = edited;
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::{
+    ecs::schedule::ShouldRun,
     math::*, 
     prelude::*,
 };
@@ -8,37 +9,21 @@ use rand::prelude::*;
 
 use crate::*;
 
+pub mod ai;
+pub mod commentary;
+pub mod controls;
+pub mod dialogue;
+pub mod highlight;
+pub mod history;
 pub mod intro;
+pub mod menu;
+pub mod notation;
 pub mod outro;
+pub mod pause;
 pub mod playing;
+pub mod screen_text;
 pub mod setup;
-
-pub struct ScreenText(pub String, pub f32, pub [f32; 3]);
-
-pub fn screen_text_display(
-    turn: Res<Turn>,
-    mut ctx: ResMut<EguiContext>,
-    text: Res<ScreenText>,
-) {
-    match *turn {
-        Turn::PreGame | Turn::PostGame => (),
-        _ => return,
-    };
-
-    let ctx = ctx.ctx_mut();
-
-    egui::Area::new("SpeechText")
-    .anchor(egui::Align2::LEFT_TOP, (0., 0.))
-    .movable(false)
-    .interactable(false)
-    .show(ctx, |ui| {
-        ui.centered_and_justified(|ui| {
-            let color = egui::Rgba::from_rgba_unmultiplied(text.2[0], text.2[1], text.2[2], text.1); // default color with alpha;
-            let text = egui::RichText::new(&text.0).color(color);
-            ui.heading(text);
-        })
-    });
-}
+pub mod sound;
 
 #[derive(Component, Debug)]
 pub struct Checker {
@@ -46,8 +31,6 @@ pub struct Checker {
     pos: IVec2,
     alive: bool,
     king: bool,
-    model: Entity,
-    king_model: Entity
 }
 
 #[derive(Component)]
@@ -63,7 +46,7 @@ pub struct CheckerMap(std::collections::HashMap<IVec2, (Entity, Team)>);
 
 impl CheckerMap {
     pub fn is_place_free(&self, pos: IVec2) -> bool {
-        pos.x >= 0 && pos.x <= 7 && pos.y >= 0 && pos.y <= 7 && !self.0.contains_key(&pos)
+        is_on_board(pos) && !self.0.contains_key(&pos)
     }
 
     pub fn get_place_team(&self, pos: IVec2) -> Option<Team> {
@@ -79,7 +62,7 @@ impl CheckerMap {
     }
 
     pub fn set_place(&mut self, pos: IVec2, value: Option<(Entity, Team)>) {
-        if pos.x < 0 || pos.x > 7 || pos.y < 0 || pos.y > 7 {
+        if !is_on_board(pos) {
             eprintln!("Attempted to set place out of bounds of board");
             return;
         }
@@ -91,62 +74,11 @@ impl CheckerMap {
     }
 
     pub fn get_jumps(&self, pos: IVec2, checker_team: Team, king: bool) -> Vec<(Vec<IVec2>, Vec<IVec2>)> {
-        use std::collections::VecDeque;
-        use std::collections::HashSet;
-
-        let opposing_team = match checker_team { Team::Red => Team::Black, Team::Black => Team::Red };
-
-        let offsets = get_piece_offsets(checker_team, king);
-
-        let mut jumps = vec![];
-
-        let mut open_set = VecDeque::new();
-        open_set.push_back((vec![], vec![], pos));
-
-        let mut computed_spaces = HashSet::new();
-
-        while !open_set.is_empty() {
-            let (hopped, hops, pos) = open_set.pop_front().unwrap();
-            let mut extended = false;
-
-            computed_spaces.insert(pos);
-
-            for offset in offsets.iter() {
-                let offset = *offset;
-
-                if let Some(t) = self.get_place_team(pos + offset) {
-                    if computed_spaces.contains(&(pos + (2 * offset))) {
-                        continue;
-                    }
-                    if t == opposing_team && self.is_place_free(pos + (2 * offset)) {
-                        let mut hops = hops.clone();
-                        let mut hopped_checkers = hopped.clone();
-                        hops.push(pos + (2 * offset));
-                        hopped_checkers.push(pos + offset);
-                        open_set.push_back((hopped_checkers, hops, pos + (2 * offset)));
-                        extended = true;
-                    }
-                }
-            }
-
-            if !extended {
-                jumps.push((hopped, hops, pos));
-            }
-        }
-
-        jumps.into_iter()
-        .filter(|(_, _, p)| *p != pos)
-        .map(|(jumped, jumps, _)| {
-            (jumped, jumps)
-        })
-        .collect()
+        find_jumps(pos, checker_team, king, |p| self.get_place_team(p))
     }
 
     pub fn get_available_moves(&self, pos: IVec2, team: Team, king: bool) -> Vec<IVec2> {
-        get_piece_offsets(team, king).into_iter()
-        .map(|o| pos + o)
-        .filter(|m| self.is_place_free(*m))
-        .collect()
+        find_moves(pos, team, king, |p| self.get_place_team(p))
     }
 
     pub fn reset(
@@ -155,6 +87,7 @@ impl CheckerMap {
         checkers: &mut Query<(Entity, &mut Checker, &Transform)>,
         mut red_stack: ResMut<RedChipStack>,
         mut black_stack: ResMut<BlackChipStack>,
+        settings: &Settings,
     ) {
         self.0.clear();
         red_stack.height = 0;
@@ -176,8 +109,11 @@ impl CheckerMap {
                     self.set_place(initial_coord, Some((id, Team::Red)));
 
                     commands.entity(id)
+                    .remove::<MultiQuadJump>()
+                    // Otherwise a checker still waiting to be dealt would drop in again after the reset
+                    .cancel_timer(DEAL_TIMER)
                     .insert(Kingify(false))
-                    .insert(QuadJump::new(tf.translation, initial_pos, 50.0, 0.5));
+                    .insert(QuadJump::arc(tf.translation, initial_pos, 0.15).at_speed(settings.animation_rate(1.0)));
 
                     r += 1;
                 },
@@ -192,8 +128,10 @@ impl CheckerMap {
                     self.set_place(initial_coord, Some((id, Team::Black)));
 
                     commands.entity(id)
+                    .remove::<MultiQuadJump>()
+                    .cancel_timer(DEAL_TIMER)
                     .insert(Kingify(false))
-                    .insert(QuadJump::new(tf.translation, initial_pos, 50.0, 0.5));
+                    .insert(QuadJump::arc(tf.translation, initial_pos, 0.15).at_speed(settings.animation_rate(1.0)));
 
                     b += 1;
                 }
@@ -221,14 +159,111 @@ pub enum Team {
     Red,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Turn {
-    PreGame,
+/// Rule variants that can be picked from the main menu
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Variant {
+    #[default]
+    Standard,
+    OptionalCaptures,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Standard, Variant::OptionalCaptures];
+
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Variant::Standard => "variant-standard",
+            Variant::OptionalCaptures => "variant-optional-captures",
+        }
+    }
+
+    /// Whether a checker that can jump has to
+    pub fn forced_captures(self) -> bool {
+        match self {
+            Variant::Standard => true,
+            Variant::OptionalCaptures => false,
+        }
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GameState {
+    Menu,
+    Intro,
     Black,
     BlackForesight,
     Red,
     RedCorrection,
-    PostGame
+    Outro,
+    /// Pushed over whichever turn it is
+    Paused,
+}
+
+impl GameState {
+    pub fn is_playing(&self) -> bool {
+        match self {
+            GameState::Black | GameState::BlackForesight | GameState::Red | GameState::RedCorrection => true,
+            GameState::Menu | GameState::Intro | GameState::Outro | GameState::Paused => false,
+        }
+    }
+}
+
+/// Run criteria for systems that matter whoever's turn it is, but only while the game is being played
+pub fn run_if_playing(state: Res<State<GameState>>) -> ShouldRun {
+    match state.current().is_playing() {
+        true => ShouldRun::Yes,
+        false => ShouldRun::No,
+    }
+}
+
+/// Run criteria for anything that reacts to the state changing. Not `State::is_changed`, which the state driver trips
+/// every frame. Chain `or_if_changed` on to also run when a resource changes.
+pub fn run_if_state_changed(state: Res<State<GameState>>, mut last_state: Local<LastState>) -> ShouldRun {
+    if *state.current() == last_state.0 {
+        return ShouldRun::No;
+    }
+
+    last_state.0 = *state.current();
+    ShouldRun::Yes
+}
+
+/// Lets through whatever the run criteria before it did, and anything on a frame where `R` has changed
+pub fn or_if_changed<R: bevy::ecs::system::Resource>(In(should_run): In<ShouldRun>, resource: Res<R>) -> ShouldRun {
+    match resource.is_changed() {
+        true => ShouldRun::Yes,
+        false => should_run,
+    }
+}
+
+/// State changes that give way to one already queued this frame. Bevy only takes one change a frame, so whichever
+/// was asked for first wins and anything asked for after it is dropped. Each returns whether its change went ahead.
+pub trait TryChangeState {
+    fn try_set(&mut self, state: GameState) -> bool;
+    fn try_push(&mut self, state: GameState) -> bool;
+    fn try_pop(&mut self) -> bool;
+}
+
+impl TryChangeState for State<GameState> {
+    fn try_set(&mut self, state: GameState) -> bool {
+        self.set(state).is_ok()
+    }
+
+    fn try_push(&mut self, state: GameState) -> bool {
+        self.push(state).is_ok()
+    }
+
+    fn try_pop(&mut self) -> bool {
+        self.pop().is_ok()
+    }
+}
+
+/// Run criteria for anything moved along by time, which stands still while a game is paused or left for the menu
+pub fn run_if_unpaused(state: Res<State<GameState>>) -> ShouldRun {
+    match state.inactives().is_empty() {
+        true => ShouldRun::Yes,
+        false => ShouldRun::No,
+    }
 }
 
 fn index_checkboard(i: i32) -> IVec2 {
@@ -280,34 +315,128 @@ fn get_piece_offsets(team: Team, king: bool) -> Vec<IVec2> {
     }
 }
 
-pub struct LastTurn(Turn);
-impl Default for LastTurn {
+fn is_on_board(pos: IVec2) -> bool {
+    pos.x >= 0 && pos.x <= 7 && pos.y >= 0 && pos.y <= 7
+}
+
+/// Finds every complete jump sequence for a checker, given a lookup of which team occupies a place.
+/// Shared by the live `CheckerMap` and the AI's `Board` so both follow the same rules.
+pub fn find_jumps(
+    pos: IVec2,
+    checker_team: Team,
+    king: bool,
+    team_at: impl Fn(IVec2) -> Option<Team>,
+) -> Vec<(Vec<IVec2>, Vec<IVec2>)> {
+    use std::collections::VecDeque;
+    use std::collections::HashSet;
+
+    let is_place_free = |p: IVec2| is_on_board(p) && team_at(p).is_none();
+
+    let opposing_team = match checker_team { Team::Red => Team::Black, Team::Black => Team::Red };
+
+    let offsets = get_piece_offsets(checker_team, king);
+
+    let mut jumps = vec![];
+
+    let mut open_set = VecDeque::new();
+    open_set.push_back((vec![], vec![], pos));
+
+    let mut computed_spaces = HashSet::new();
+
+    while !open_set.is_empty() {
+        let (hopped, hops, pos) = open_set.pop_front().unwrap();
+        let mut extended = false;
+
+        computed_spaces.insert(pos);
+
+        for offset in offsets.iter() {
+            let offset = *offset;
+
+            if let Some(t) = team_at(pos + offset) {
+                if computed_spaces.contains(&(pos + (2 * offset))) {
+                    continue;
+                }
+                if t == opposing_team && is_place_free(pos + (2 * offset)) {
+                    let mut hops = hops.clone();
+                    let mut hopped_checkers = hopped.clone();
+                    hops.push(pos + (2 * offset));
+                    hopped_checkers.push(pos + offset);
+                    open_set.push_back((hopped_checkers, hops, pos + (2 * offset)));
+                    extended = true;
+                }
+            }
+        }
+
+        if !extended {
+            jumps.push((hopped, hops, pos));
+        }
+    }
+
+    jumps.into_iter()
+    .filter(|(_, _, p)| *p != pos)
+    .map(|(jumped, jumps, _)| {
+        (jumped, jumps)
+    })
+    .collect()
+}
+
+/// Finds every single-step move for a checker, given a lookup of which team occupies a place.
+pub fn find_moves(pos: IVec2, team: Team, king: bool, team_at: impl Fn(IVec2) -> Option<Team>) -> Vec<IVec2> {
+    get_piece_offsets(team, king).into_iter()
+    .map(|o| pos + o)
+    .filter(|m| is_on_board(*m) && team_at(*m).is_none())
+    .collect()
+}
+
+pub struct LastState(GameState);
+impl Default for LastState {
     fn default() -> Self {
-        Self(Turn::PreGame)
+        Self(GameState::Menu)
     }
 }
 
+/// Runs when the state or language changes
 pub fn update_header_with_turn(
-    turn: Res<Turn>,
-    mut last_turn: Local<LastTurn>,
-    mut header: ResMut<Header>
+    state: Res<State<GameState>>,
+    mut header: ResMut<Header>,
+    locale: Res<Locale>,
 ) {
-    if *turn != last_turn.0 {
-        header.0 = Cow::Borrowed(match *turn {
-            Turn::PreGame => "",
-            Turn::Black => "Black's Turn",
-            Turn::BlackForesight => "Choose your next move",
-            Turn::Red => "Your Turn",
-            Turn::RedCorrection => "Your chosen move was invalidated by Black, choose another",
-            Turn::PostGame => "",
-        });
-
-        last_turn.0 = *turn;
-    }
+    let id = match state.current() {
+        GameState::Menu => None,
+        GameState::Intro => None,
+        GameState::Black => Some("header-black-turn"),
+        GameState::BlackForesight => Some("header-black-foresight"),
+        GameState::Red => Some("header-red-turn"),
+        GameState::RedCorrection => Some("header-red-correction"),
+        GameState::Outro => None,
+        GameState::Paused => Some("header-paused"),
+    };
+    header.0 = match id {
+        Some(id) => Cow::Owned(locale.get(id)),
+        None => Cow::Borrowed(""),
+    };
 }
 
 pub struct Header(pub Cow<'static, str>);
 
+/// Shows the seed in a corner so it can be included in bug reports
+pub fn seed_display(
+    mut ctx: ResMut<EguiContext>,
+    rng: Res<GameRng>,
+    locale: Res<Locale>,
+) {
+    let mut args = FluentArgs::new();
+    args.set("seed", rng.seed().to_string());
+
+    egui::Area::new("Seed")
+    .anchor(egui::Align2::LEFT_BOTTOM, (4., -4.))
+    .movable(false)
+    .interactable(false)
+    .show(ctx.ctx_mut(), |ui| {
+        ui.label(egui::RichText::new(locale.get_args("seed", Some(&args))).small().weak());
+    });
+}
+
 pub fn text_header(
     mut ctx: ResMut<EguiContext>,
     header: Res<Header>,
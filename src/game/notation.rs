@@ -0,0 +1,164 @@
+use super::*;
+
+/// A complete move for one checker: where it started, which places it hopped over and where it landed after each hop.
+/// A simple move has one entry in `jumps` and nothing in `jumped`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub start: IVec2,
+    pub jumped: Vec<IVec2>,
+    pub jumps: Vec<IVec2>,
+}
+
+impl Move {
+    pub fn dest(&self) -> IVec2 {
+        *self.jumps.last().unwrap()
+    }
+
+    pub fn is_capture(&self) -> bool {
+        !self.jumped.is_empty()
+    }
+}
+
+/// Standard draughts square number (1-32) for a dark square, counted from Black's side of the board.
+pub fn square_number(pos: IVec2) -> u32 {
+    ((7 - pos.y) * 4 + pos.x / 2 + 1) as u32
+}
+
+/// Board coordinate for a standard draughts square number (1-32).
+pub fn square_pos(square: u32) -> Option<IVec2> {
+    if !(1..=32).contains(&square) {
+        return None;
+    }
+
+    let i = square as i32 - 1;
+    let y = 7 - (i / 4);
+    let x = (i % 4) * 2 + (y % 2);
+
+    Some(ivec2(x, y))
+}
+
+/// Writes a move the way PDN does, e.g. `11-15` or `22x15x8`.
+pub fn move_notation(mov: &Move) -> String {
+    let sep = if mov.is_capture() { "x" } else { "-" };
+
+    let mut out = square_number(mov.start).to_string();
+    for hop in mov.jumps.iter() {
+        out.push_str(sep);
+        out.push_str(&square_number(*hop).to_string());
+    }
+
+    out
+}
+
+/// Reads the squares out of a move written like `11-15` or `22x15x8`, from the start through every hop
+pub fn parse_move_squares(text: &str) -> Option<Vec<IVec2>> {
+    let squares = text.trim()
+    .split(['-', 'x', 'X'])
+    .map(|s| s.trim().parse().ok().and_then(square_pos))
+    .collect::<Option<Vec<_>>>()?;
+
+    (squares.len() >= 2).then_some(squares)
+}
+
+/// A move that was actually played, as kept in a `GameLog`
+#[derive(Clone, Debug)]
+pub struct PlayedMove {
+    pub team: Team,
+    pub mov: Move,
+    /// For a Red correction, the foretold move that Black invalidated
+    pub replaced: Option<Move>,
+}
+
+/// Every move played so far in the current game
+#[derive(Default)]
+pub struct GameLog(pub Vec<PlayedMove>);
+
+/// PDN movetext for a list of moves, numbering each Black move and its Red reply together.
+/// Invalidated foretold moves are written as comments ahead of the correction.
+pub fn movetext(moves: &[PlayedMove]) -> String {
+    let mut out = String::new();
+    let mut number = 0;
+
+    for played in moves {
+        if played.team == Team::Black {
+            number += 1;
+            out.push_str(&format!("{}. ", number));
+        }
+
+        if let Some(replaced) = &played.replaced {
+            out.push_str(&format!("{{foretold {}}} ", move_notation(replaced)));
+        }
+
+        out.push_str(&move_notation(&played.mov));
+        out.push(' ');
+    }
+
+    out
+}
+
+/// A full PDN game: tag pairs, then the movetext ending in the result.
+/// PDN puts the side that moves first, Black, first in the result, so `1-0` is a win for Black.
+pub fn pdn_game(tags: &[(&str, String)], moves: &[PlayedMove], winner: Option<Team>) -> String {
+    let result = match winner {
+        Some(Team::Black) => "1-0",
+        Some(Team::Red) => "0-1",
+        None => "1/2-1/2",
+    };
+
+    let mut out = String::new();
+    for (name, value) in tags {
+        out.push_str(&format!("[{} \"{}\"]\n", name, value));
+    }
+    out.push_str(&format!("[GameType \"21\"]\n[Result \"{}\"]\n\n", result));
+    out.push_str(&movetext(moves));
+    out.push_str(result);
+    out.push_str("\n\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_numbers_round_trip() {
+        for square in 1..=32 {
+            let pos = square_pos(square).unwrap();
+
+            assert!(is_on_board(pos));
+            // Only the dark squares are numbered
+            assert_eq!((pos.x + pos.y) % 2, 0);
+            assert_eq!(square_number(pos), square);
+        }
+
+        assert_eq!(square_pos(0), None);
+        assert_eq!(square_pos(33), None);
+    }
+
+    #[test]
+    fn parses_moves_and_captures() {
+        assert_eq!(parse_move_squares("11-15"), Some(vec![square_pos(11).unwrap(), square_pos(15).unwrap()]));
+        assert_eq!(parse_move_squares(" 22x15X8 ").map(|s| s.len()), Some(3));
+    }
+
+    #[test]
+    fn refuses_malformed_moves() {
+        for text in ["", "11", "11-", "-15", "0-4", "11-33", "a-b", "11/15", "11--15"] {
+            assert_eq!(parse_move_squares(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn result_tag_puts_black_first() {
+        let results = [(Some(Team::Black), "1-0"), (Some(Team::Red), "0-1"), (None, "1/2-1/2")];
+
+        for (winner, result) in results {
+            let pdn = pdn_game(&[("Event", "Test".to_string())], &[], winner);
+
+            assert!(pdn.starts_with("[Event \"Test\"]\n"));
+            assert!(pdn.contains(&format!("[Result \"{}\"]", result)));
+            assert!(pdn.trim_end().ends_with(result));
+        }
+    }
+}
@@ -1,187 +1,66 @@
 use super::*;
-use std::time::Duration;
 
 pub struct EndGameEvent(pub Team);
 
 pub fn end(
+    mut commands: Commands,
     mut events: EventReader<EndGameEvent>,
-    turn: Res<Turn>,
     // mut checkers: Query<(&mut Checker, &mut LerpToTarget)>,
-    mut phase: ResMut<EndingPhase>,
-    mut ending_moment: ResMut<EndingDurationSinceStartup>,
-    mut camera: Query<(&mut LerpToTarget, &mut SlerpToTarget), (With<Camera>, Without<Checker>)>,
+    mut runner: ResMut<DialogueRunner>,
+    scripts: Res<DialogueScripts>,
+    camera: Query<Entity, With<PlayerCamera>>,
     mut text: ResMut<ScreenText>,
-    time: Res<Time>,
+    log: Res<GameLog>,
+    rng: Res<GameRng>,
 ) {
-    if *turn != Turn::PostGame {
-        return; 
-    }
-
-    let mut end = false;
+    let mut winner = None;
 
     for e in events.iter() {
-        *phase = match e.0 {
-            Team::Black => EndingPhase::black(),
-            Team::Red => EndingPhase::red(),
-        };
+        winner = Some(e.0);
 
         println!("Processed endgame event!");
 
-        end = true;
+        save_game(&log, rng.seed(), e.0);
     }
 
-    if !end {
-        return;
-    }
+    let winner = match winner {
+        Some(winner) => winner,
+        None => return,
+    };
 
-    if let Ok((mut lerp, mut slerp)) = camera.get_single_mut() {
-        lerp.target = LEAN_BACK_POS.into();
-        lerp.ratio = 5.0;
-
-        slerp.target = Quat::from_scaled_axis(LEAN_BACK_ANGLE.into());
-        slerp.ratio = 5.0;
+    if let Ok(camera) = camera.get_single() {
+        lean_camera(&mut commands.entity(camera), LEAN_BACK_POS, LEAN_BACK_ANGLE, LEAN_SECS);
     }
 
-    *text = ScreenText("".to_string(), 1.0, [0.5, 0.5, 0.5]);
-
-    ending_moment.0 = time.time_since_startup();
+    let script = match winner {
+        Team::Black => scripts.black_wins.clone(),
+        Team::Red => scripts.red_wins.clone(),
+    };
+    runner.start(script, &mut text);
 }
 
-pub struct EndingDurationSinceStartup(pub Duration);
-
-pub enum EndingPhase {
-    Red(RedEndingPhase),
-    Black(BlackEndingPhase),
-}
-
-impl EndingPhase {
-    pub fn red() -> Self {
-        EndingPhase::Red(RedEndingPhase::Empty)
-    }
-
-    pub fn black() -> Self {
-        EndingPhase::Black(BlackEndingPhase::Empty)
+/// Appends the finished game to `foretold.pdn`, along with the seed it was played with
+fn save_game(log: &GameLog, seed: u64, winner: Team) {
+    use std::io::Write;
+
+    let pdn = pdn_game(
+        &[
+            ("Event", "Foretold".to_string()),
+            ("Black", "Foretold".to_string()),
+            ("White", "Player".to_string()),
+            ("Seed", seed.to_string()),
+        ],
+        &log.0,
+        Some(winner),
+    );
+
+    let saved = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open("foretold.pdn")
+    .and_then(|mut file| file.write_all(pdn.as_bytes()));
+
+    if let Err(e) = saved {
+        eprintln!("Failed to save game record: {}", e);
     }
 }
-
-pub enum RedEndingPhase {
-    Empty, // Wait one second
-    OhItAppears, // Oh. It appears you've beaten me.
-    YouKnow, // R: You know,  
-    ForSomeone, // R: for someone that can see the future,
-    YouDont, // R: you don't plan ahead an awful lot.
-    Ellipse, // ...
-    Fall, // R: Fall off the table
-}
-
-pub enum BlackEndingPhase {
-    Empty, // Wait one second
-    Aha, // Aha. I've won.
-    Again, // Let's play again. (immediately reset game state)
-}
-
-pub fn update_end_game_text(
-    mut commands: Commands,
-    mut turn: ResMut<Turn>,
-    mut text: ResMut<ScreenText>,
-    mut phase: ResMut<EndingPhase>,
-    time: Res<Time>,
-    ending_moment: Res<EndingDurationSinceStartup>,
-    mut exit: EventWriter<bevy::app::AppExit>,
-    mut checkers: Query<(Entity, &mut Checker, &Transform)>,
-    mut map: ResMut<CheckerMap>,
-    red_stack: ResMut<RedChipStack>,
-    black_stack: ResMut<BlackChipStack>,
-    mut camera: Query<(Entity, &Transform), (With<PlayerCamera>, Without<Checker>)>,
-) {
-    if *turn != Turn::PostGame {
-        return;
-    }
-
-    let timer = (time.time_since_startup() - ending_moment.0).as_secs_f64();
-
-    match &mut *phase {
-        EndingPhase::Red(phase) => match phase {
-            RedEndingPhase::Empty => {
-                if timer > 3.0 {
-                    text.0.push_str("Oh. It appears you've beaten me.");
-                    *phase = RedEndingPhase::OhItAppears;
-                }
-            },
-            RedEndingPhase::OhItAppears => {
-                if timer > 6.0 {
-                    text.0 = "You know,".to_string(); 
-                    text.2 = [0.8, 0.0, 0.0];
-                    *phase = RedEndingPhase::YouKnow;
-                }
-            },
-            RedEndingPhase::YouKnow => {
-                if timer > 7.5 {
-                    text.0.push_str(" for someone that can see the future,");
-                    *phase = RedEndingPhase::ForSomeone;
-                }
-            },
-            RedEndingPhase::ForSomeone => {
-                if timer > 9.0 {
-                    text.0.push_str("\nyou don't plan ahead an awful lot.");
-                    *phase = RedEndingPhase::YouDont;
-                }
-            },
-            RedEndingPhase::YouDont => {
-                if timer > 13.0 {
-                    text.0 = "...".to_string();
-                    text.2 = [0.5, 0.5, 0.5];
-                    *phase = RedEndingPhase::Ellipse;
-                }
-            },
-            RedEndingPhase::Ellipse => {
-                if timer > 18.0 {
-                    text.0 = "".to_string();
-                    *phase = RedEndingPhase::Fall;
-
-                    // TODO: Fall
-
-                    let (cam, tf) = camera.single_mut();
-                    commands.entity(cam)
-                    .insert(QuadJump::new(
-                        tf.translation,
-                        vec3(0.0, -15.0, -20.0),
-                        0.1,
-                        5.0
-                    ))
-                    .insert(StareAt(vec3(0.0, 0.25, 0.0)));
-                }
-            },
-            RedEndingPhase::Fall => {
-                if timer > 23.0 {
-                    exit.send(bevy::app::AppExit);
-                }
-            },
-        },
-        EndingPhase::Black(phase) => match phase {
-            BlackEndingPhase::Empty => {
-                if timer > 1.0 {
-                    text.0.push_str("Aha. I've won.");
-                    *phase = BlackEndingPhase::Aha;
-                }
-            },
-            BlackEndingPhase::Aha => {
-                if timer > 3.0 {
-                    text.0.push_str("\nLet's play again.");
-                    *phase = BlackEndingPhase::Again; 
-
-                    map.reset(&mut commands, &mut checkers, red_stack, black_stack);
-                }
-            },
-            BlackEndingPhase::Again => {
-                if timer > 4.0 {
-                    if text.1 > 0.0 {
-                        text.1 = (text.1 - (2.0 * time.delta_seconds())).clamp(0.0, 1.0);
-                    } else {
-                        *turn = Turn::BlackForesight;
-                    }
-                }
-            },
-        },
-    }
-}
\ No newline at end of file
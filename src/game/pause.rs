@@ -0,0 +1,106 @@
+use super::*;
+
+/// Puts every checker back and starts a fresh game, going on to the given state if there is one
+pub struct RestartEvent(pub Option<GameState>);
+
+pub fn restart(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    mut state: ResMut<State<GameState>>,
+    mut map: ResMut<CheckerMap>,
+    mut checkers: Query<(Entity, &mut Checker, &Transform)>,
+    red_stack: ResMut<RedChipStack>,
+    black_stack: ResMut<BlackChipStack>,
+    mut red_move: ResMut<RedMove>,
+    mut selected: ResMut<SelectedChecker>,
+    mut log: ResMut<GameLog>,
+    mut rng: ResMut<GameRng>,
+    settings: Res<Settings>,
+) {
+    let next = match events.iter().last() {
+        Some(e) => e.0,
+        None => return,
+    };
+
+    map.reset(&mut commands, &mut checkers, red_stack, black_stack, &settings);
+    *red_move = RedMove { start: ivec2(-1, -1), jumped: vec![], jumps: vec![] };
+    selected.0 = None;
+    log.0.clear();
+    rng.next_game();
+
+    // Replacing rather than setting also drops the turn a pause or the menu was pushed over. The board has already
+    // been reset, so this wins over anything else queued this frame.
+    if let Some(next) = next {
+        let _ = state.overwrite_replace(next);
+    }
+}
+
+/// Escape pauses the game, or resumes it when already paused
+pub fn pause(
+    mut state: ResMut<State<GameState>>,
+    input: Res<Input<KeyCode>>,
+) {
+    if !input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match *state.current() {
+        GameState::Paused => { state.try_pop(); },
+        s if s.is_playing() => { state.try_push(GameState::Paused); },
+        _ => (),
+    }
+}
+
+pub fn pause_menu(
+    mut ctx: ResMut<EguiContext>,
+    mut state: ResMut<State<GameState>>,
+    mut restarts: EventWriter<RestartEvent>,
+    mut continuing: ResMut<ContinueState>,
+    mut settings: ResMut<Settings>,
+    mut showing_settings: Local<bool>,
+    locale: Res<Locale>,
+) {
+    let mut edited = settings.clone();
+
+    egui::Area::new("PauseMenu")
+    .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+    .movable(false)
+    .show(ctx.ctx_mut(), |ui| {
+        egui::Frame::none()
+        .fill(egui::Color32::from_rgba_unmultiplied(21, 22, 19, 220))
+        .margin((32., 24.))
+        .show(ui, |ui| {
+            if *showing_settings {
+                if settings_ui(ui, &mut edited, &locale) {
+                    edited.save();
+                    *showing_settings = false;
+                }
+                return;
+            }
+
+            if ui.button(locale.get("pause-resume")).clicked() {
+                state.try_pop();
+            }
+
+            if ui.button(locale.get("pause-restart")).clicked() {
+                restarts.send(RestartEvent(Some(GameState::BlackForesight)));
+            }
+
+            if ui.button(locale.get("menu-settings")).clicked() {
+                *showing_settings = true;
+            }
+
+            if ui.button(locale.get("pause-quit-to-menu")).clicked() {
+                // Swaps the pause for the menu, leaving the game underneath for Continue
+                let under = *state.inactives().last().unwrap();
+                if state.try_set(GameState::Menu) {
+                    continuing.0 = Some(under);
+                }
+            }
+        });
+    });
+
+    if edited != *settings {
+        *settings = edited;
+    }
+}
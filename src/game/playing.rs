@@ -5,34 +5,153 @@ pub const LEAN_BACK_ANGLE: [f32; 3] = [0.0, PI, 0.0];
 pub const LEAN_FORWARD_POS: [f32; 3] = [0.0, 1.3, 0.0];
 pub const LEAN_FORWARD_ANGLE: [f32; 3] = [0.0, PI / std::f32::consts::SQRT_2, PI / std::f32::consts::SQRT_2];
 
-pub fn movement(mut q: Query<(&mut LerpToTarget, &mut SlerpToTarget), With<Camera>>, input: Res<Input<KeyCode>>, turn: Res<Turn>) {
-    let lerp_ratio = 5.0;
-    let slerp_ratio = 5.0;
+/// What the orbit camera circles around, the middle of the board
+const ORBIT_FOCUS: [f32; 3] = [0.0, 0.085, 0.0];
+const ORBIT_PITCH: std::ops::RangeInclusive<f32> = 0.05..=1.5;
+const ORBIT_DISTANCE: std::ops::RangeInclusive<f32> = 0.4..=2.0;
+/// Radians turned per pixel dragged
+const ORBIT_DRAG_SPEED: f32 = 0.005;
+/// How long the camera takes to swing to a preset view
+pub const LEAN_SECS: f32 = 0.8;
+/// How far above the board checkers rise when they move, in world units
+const HOP_HEIGHT: f32 = 0.04;
+/// How much checkers flatten as they land
+pub const LANDING_SQUASH: f32 = 0.2;
+
+/// Where the orbit camera is around the board, in spherical coordinates
+pub struct Orbit {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+}
 
-    match *turn {
-        Turn::Black | Turn::Red | Turn::BlackForesight | Turn::RedCorrection => (),
-        Turn::PreGame | Turn::PostGame => return,
-    };
+impl Orbit {
+    /// The orbit that puts the camera at `pos`, as near as the bounds allow
+    pub fn from_pos(pos: Vec3) -> Self {
+        let offset = pos - Vec3::from(ORBIT_FOCUS);
+        let distance = offset.length();
+
+        let mut orbit = Orbit {
+            yaw: offset.x.atan2(offset.z),
+            pitch: (offset.y / distance).asin(),
+            distance,
+        };
+        orbit.clamp();
+        orbit
+    }
+
+    fn clamp(&mut self) {
+        self.pitch = self.pitch.clamp(*ORBIT_PITCH.start(), *ORBIT_PITCH.end());
+        self.distance = self.distance.clamp(*ORBIT_DISTANCE.start(), *ORBIT_DISTANCE.end());
+    }
+
+    pub fn position(&self) -> Vec3 {
+        Vec3::from(ORBIT_FOCUS) + self.distance * vec3(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        )
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Transform::from_translation(self.position()).looking_at(ORBIT_FOCUS.into(), Vec3::Y).rotation
+    }
+}
+
+impl Default for Orbit {
+    fn default() -> Self {
+        Orbit::from_pos(LEAN_BACK_POS.into())
+    }
+}
+
+/// Swings the camera to one of the preset views, taking over from any orbiting still easing along
+pub fn lean_camera(camera: &mut bevy::ecs::system::EntityCommands, pos: [f32; 3], angle: [f32; 3], secs: f32) {
+    camera
+    .remove::<LerpToTarget>()
+    .remove::<SlerpToTarget>()
+    .insert(Animation::new(Track::parallel([
+        Track::translation(pos.into(), secs, Ease::CubicOut),
+        Track::rotation(Quat::from_scaled_axis(angle.into()), secs, Ease::CubicOut),
+    ])));
+}
+
+/// W or the right bumper leans in over the board, S or the left bumper leans back. Bevy calls the bumpers
+/// `RightTrigger` and `LeftTrigger`, and the triggers `RightTrigger2` and `LeftTrigger2`.
+pub fn movement(
+    mut commands: Commands,
+    q: Query<Entity, With<PlayerCamera>>,
+    input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    settings: Res<Settings>,
+    mut orbit: ResMut<Orbit>,
+) {
+    let secs = LEAN_SECS / settings.camera_sensitivity;
 
-    if input.just_pressed(KeyCode::W) {
-        if let Ok((mut lerp, mut slerp)) = q.get_single_mut() {
-            lerp.target = LEAN_FORWARD_POS.into();
-            lerp.ratio = lerp_ratio;
+    let bumper = |button| gamepads.iter().any(|g| gamepad_buttons.just_pressed(GamepadButton(*g, button)));
 
-            slerp.target = Quat::from_scaled_axis(LEAN_FORWARD_ANGLE.into());
-            slerp.ratio = slerp_ratio;
+    if input.just_pressed(KeyCode::W) || bumper(GamepadButtonType::RightTrigger) {
+        if let Ok(camera) = q.get_single() {
+            lean_camera(&mut commands.entity(camera), LEAN_FORWARD_POS, LEAN_FORWARD_ANGLE, secs);
         }
+
+        // So the next drag carries on from the preset
+        *orbit = Orbit::from_pos(LEAN_FORWARD_POS.into());
     }
 
-    if input.just_pressed(KeyCode::S) {
-        if let Ok((mut lerp, mut slerp)) = q.get_single_mut() {
-            lerp.target = LEAN_BACK_POS.into();
-            lerp.ratio = lerp_ratio;
+    if input.just_pressed(KeyCode::S) || bumper(GamepadButtonType::LeftTrigger) {
+        if let Ok(camera) = q.get_single() {
+            lean_camera(&mut commands.entity(camera), LEAN_BACK_POS, LEAN_BACK_ANGLE, secs);
+        }
+
+        *orbit = Orbit::from_pos(LEAN_BACK_POS.into());
+    }
+}
 
-            slerp.target = Quat::from_scaled_axis(LEAN_BACK_ANGLE.into());
-            slerp.ratio = slerp_ratio;
+/// Dragging with the right mouse button circles the camera around the board, and scrolling zooms.
+/// Only moves the camera's targets, so it eases along rather than snapping.
+pub fn orbit_camera(
+    mut commands: Commands,
+    q: Query<Entity, With<PlayerCamera>>,
+    buttons: Res<Input<MouseButton>>,
+    mut motion: EventReader<bevy::input::mouse::MouseMotion>,
+    mut wheel: EventReader<bevy::input::mouse::MouseWheel>,
+    settings: Res<Settings>,
+    mut orbit: ResMut<Orbit>,
+) {
+    let mut moved = false;
+    let dragging = settings.orbit_camera && buttons.pressed(MouseButton::Right);
+
+    // Both are read every frame, even when they're ignored, so old motion isn't applied the moment they aren't
+    for m in motion.iter() {
+        if dragging {
+            orbit.yaw -= m.delta.x * ORBIT_DRAG_SPEED * settings.camera_sensitivity;
+            orbit.pitch += m.delta.y * ORBIT_DRAG_SPEED * settings.camera_sensitivity;
+            moved = true;
         }
     }
+
+    for w in wheel.iter().filter(|_| settings.orbit_camera) {
+        let lines = match w.unit {
+            bevy::input::mouse::MouseScrollUnit::Line => w.y,
+            bevy::input::mouse::MouseScrollUnit::Pixel => w.y / 100.0,
+        };
+        orbit.distance *= 1.0 - (lines * 0.1);
+        moved = true;
+    }
+
+    if !moved {
+        return;
+    }
+
+    orbit.clamp();
+
+    if let Ok(camera) = q.get_single() {
+        commands.entity(camera)
+        .remove::<Animation>()
+        .insert(LerpToTarget { ratio: 10.0 * settings.camera_sensitivity, target: orbit.position() })
+        .insert(SlerpToTarget { ratio: 10.0 * settings.camera_sensitivity, target: orbit.rotation() });
+    }
 }
 
 pub struct SelectedChecker(pub Option<Entity>);
@@ -41,25 +160,149 @@ pub struct RedMove {
     pub start: IVec2,
     pub jumped: Vec<IVec2>,
     pub jumps: Vec<IVec2>,
-    pub performed: bool,
+}
+
+impl RedMove {
+    pub fn as_move(&self) -> Move {
+        Move { start: self.start, jumped: self.jumped.clone(), jumps: self.jumps.clone() }
+    }
+}
+
+/// The player choosing whatever is on a square: one of their checkers to move, or where to move it.
+/// Every kind of input ends up as one of these, so they all play by the same rules.
+pub struct ChooseSquareEvent(pub IVec2);
+
+/// Turns mouse picks into the square that was picked. Clicking off the board lets go of the selected checker.
+pub fn pick_squares(
+    mut events: EventReader<PickingEvent>,
+    checker_meshes: Query<&CheckerMesh>,
+    checkers: Query<&Checker>,
+    places: Query<&CheckerPlace>,
+    mut squares: EventWriter<ChooseSquareEvent>,
+    mut selected: ResMut<SelectedChecker>,
+) {
+    let mut deselected = false;
+    let mut picked = false;
+
+    for event in events.iter() {
+        match event {
+            PickingEvent::Selection(SelectionEvent::JustSelected(entity)) => {
+                picked = true;
+
+                // Picking hits the checker's mesh rather than the checker itself
+                let pos = match checker_meshes.get(*entity) {
+                    Ok(mesh) => checkers.get(mesh.0).map(|c| c.pos).ok(),
+                    Err(_) => places.get(*entity).map(|p| p.pos).ok(),
+                };
+
+                // Captured checkers sitting in the stacks aren't on a square
+                if let Some(pos) = pos.filter(|p| is_on_board(*p)) {
+                    squares.send(ChooseSquareEvent(pos));
+                }
+            },
+            PickingEvent::Selection(SelectionEvent::JustDeselected(entity)) => {
+                if let Ok(mesh) = checker_meshes.get(*entity) {
+                    deselected |= selected.0 == Some(mesh.0);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    // Picking a square deselects the checker's mesh too, but the checker has to stay selected to move there
+    if deselected && !picked {
+        selected.0 = None;
+    }
+}
+
+/// A move typed out in draughts notation, like `11-15` or `22x15x8`
+pub struct TypedMoveEvent(pub String);
+
+/// Plays typed moves by choosing their start and then their destination, as a click on each would
+pub fn play_typed_moves(
+    mut typed: EventReader<TypedMoveEvent>,
+    mut squares: EventWriter<ChooseSquareEvent>,
+    map: Res<CheckerMap>,
+    checkers: Query<&Checker>,
+    mut places: Query<&mut CheckerPlace>,
+    place_map: Res<PlaceMap>,
+    variant: Res<Variant>,
+    mut header: ResMut<Header>,
+    mut pending: Local<Option<Move>>,
+    locale: Res<Locale>,
+    selected: Res<SelectedChecker>,
+    state: Res<State<GameState>>,
+) {
+    // The destination waits a frame, until the start has been selected and its moves worked out. It's dropped if
+    // selecting the start didn't work, or the turn has moved on.
+    if let Some(mov) = pending.take() {
+        let choosing = matches!(state.current(), GameState::BlackForesight | GameState::RedCorrection);
+        if choosing && selected.0.is_some() && selected.0 == map.get_place_checker(mov.start) {
+            // Jumps down different paths can end on the same place, which only keeps one of them
+            if let Ok(mut place) = places.get_mut(place_map.get(mov.dest())) {
+                if place.valid {
+                    place.jumped = mov.jumped.clone();
+                    place.jumps = mov.jumps.clone();
+                }
+            }
+
+            squares.send(ChooseSquareEvent(mov.dest()));
+        }
+    }
+
+    let board = Board::from_checkers(checkers.iter()).with_variant(*variant);
+
+    for TypedMoveEvent(text) in typed.iter() {
+        match find_typed_move(text, &board) {
+            Ok(mov) => {
+                squares.send(ChooseSquareEvent(mov.start));
+                *pending = Some(mov);
+            },
+            Err(id) => {
+                let mut args = FluentArgs::new();
+                args.set("move", text.clone());
+                header.0 = Cow::Owned(locale.get_args(id, Some(&args)));
+            },
+        }
+    }
+}
+
+/// The move Red means by a typed one. Giving only the start and destination of a jump is enough, but any hops given
+/// in between have to match. If it can't be played, gives the header saying why.
+fn find_typed_move(text: &str, board: &Board) -> Result<Move, &'static str> {
+    let squares = parse_move_squares(text).ok_or("header-not-a-move")?;
+    let (start, path) = squares.split_first().ok_or("header-not-a-move")?;
+
+    let matches = |m: &Move| m.start == *start && (m.jumps == path || (path.len() == 1 && m.dest() == path[0]));
+
+    if let Some(mov) = board.legal_moves(Team::Red).into_iter().find(matches) {
+        return Ok(mov);
+    }
+
+    // Red could make it, if only it didn't have to capture instead
+    match board.clone().with_variant(Variant::OptionalCaptures).legal_moves(Team::Red).iter().any(matches) {
+        true => Err("header-forced-capture"),
+        false => Err("header-not-a-move"),
+    }
 }
 
 pub fn selecting(
     mut commands: Commands,
-    mut events: EventReader<PickingEvent>, 
-    checker_meshes: Query<&CheckerMesh>, 
+    mut squares: EventReader<ChooseSquareEvent>,
     mut checkers: Query<(Entity, &mut Checker)>,
     places: Query<&CheckerPlace>, 
+    place_map: Res<PlaceMap>,
     mut selected: ResMut<SelectedChecker>,
     mut selected_events: EventWriter<SelectedCheckerEvent>,
     mut map: ResMut<CheckerMap>,
-    mut turn: ResMut<Turn>,
+    mut state: ResMut<State<GameState>>,
     mut red_move: ResMut<RedMove>,
     mut header: ResMut<Header>,
     mut red_stack: ResMut<RedChipStack>,
     mut black_stack: ResMut<BlackChipStack>,
-    input: Res<Input<KeyCode>>,
-    mut end_game: EventWriter<EndGameEvent>,
+    mut log: ResMut<GameLog>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
 ) {
     if let Some(c) = selected.0 {
         if let Ok((_, checker)) = checkers.get(c) {
@@ -69,104 +312,105 @@ pub fn selecting(
         }
     }
 
-    if input.just_pressed(KeyCode::F3) {
-        end_game.send(EndGameEvent(Team::Red));
-        *turn = Turn::PostGame;
-        return;
-    }
+    for ChooseSquareEvent(pos) in squares.iter() {
+        let pos = *pos;
+        if !is_on_board(pos) {
+            continue;
+        }
 
-    for event in events.iter() {
-        match event {
-            PickingEvent::Selection(e) => {
-                match e {
-                    SelectionEvent::JustSelected(entity) => {
-                        // Selecting a checker
-                        if let Ok(mesh) = checker_meshes.get(*entity) {
-                            selected.0 = Some(mesh.0);
-                            selected_events.send(SelectedCheckerEvent(mesh.0));
+        // Selecting a checker
+        if let Some((checker, Team::Red)) = map.get_place(pos) {
+            selected.0 = Some(checker);
+            selected_events.send(SelectedCheckerEvent(checker));
+            continue;
+        }
+
+        let place = places.get(place_map.get(pos)).unwrap();
+
+        match state.current() {
+            GameState::BlackForesight => {
+                // Moving a checker
+                if !place.valid {
+                    println!("Value at place: {:?}", map.get_place(place.pos));
+                    header.0 = Cow::Owned(locale.get("header-invalid-destination"));
+                    return;
+                }
+
+                if let Some(checker_id) = selected.0 {
+                    if let Ok((_, checker)) = checkers.get_mut(checker_id) {
+                        if !state.try_set(GameState::Black) {
+                            return;
                         }
-                        match *turn {                            
-                            Turn::BlackForesight => {
-                                // Moving a checker
-                                if let Ok(place) = places.get(*entity) {
-                                    if !place.valid {
-                                        println!("Value at place: {:?}", map.get_place(place.pos));
-                                        header.0 = Cow::Borrowed("You cannot move that checker to that position");
-                                        return;
-                                    }
-
-                                    if let Some(checker_id) = selected.0 {
-                                        if let Ok((_, checker)) = checkers.get_mut(checker_id) {
-                                            println!("Moving!");
-
-                                            *red_move = RedMove {
-                                                start: checker.pos, 
-                                                jumped: place.jumped.clone(),
-                                                jumps: place.jumps.clone(),
-                                                performed: false,
-                                            };
-            
-                                            selected.0 = None;
-                                            *turn = Turn::Black;
-                                        }
-                                    }
-                                }
-                            },
-                            Turn::RedCorrection => {
-                                if let Ok(place) = places.get(*entity) {
-                                    if !place.valid {
-                                        header.0 = Cow::Borrowed("You cannot move that checker to that position");
-                                        return;
-                                    }
-
-                                    if let Some(checker_id) = selected.0 {
-                                        if let Ok((_, checker)) = checkers.get_mut(checker_id) {
-                                            move_piece(&mut commands, &mut map, checker_id, checker, place.jumps.clone());
-            
-                                            for pos in place.jumped.iter() {
-                                                let pos = *pos;
 
-                                                let target = map.get_place_checker(pos)
-                                                .expect(&format!("Failed to get checker at pos {:?}", pos));
-                                                let (_, checker) = checkers.get_mut(target).unwrap();
+                        println!("Moving!");
 
-                                                println!("Kill piece 3!");
-                                                kill_piece(&mut commands, &mut map, checker, &mut red_stack, &mut black_stack);
-                                            }
-            
-                                            selected.0 = None;
-                                            *turn = Turn::BlackForesight;
-                                        }
-                                    }
-                                }
-                            },
-                            _ => ()
+                        *red_move = RedMove {
+                            start: checker.pos, 
+                            jumped: place.jumped.clone(),
+                            jumps: place.jumps.clone(),
+                        };
+
+                        selected.0 = None;
+                    }
+                }
+            },
+            GameState::RedCorrection => {
+                if !place.valid {
+                    header.0 = Cow::Owned(locale.get("header-invalid-destination"));
+                    return;
+                }
+
+                if let Some(checker_id) = selected.0 {
+                    if let Ok((_, checker)) = checkers.get_mut(checker_id) {
+                        if !state.try_set(GameState::BlackForesight) {
+                            return;
                         }
-                    },
-                    SelectionEvent::JustDeselected(entity) => {
-                        // Deselecting a checker
-                        if selected.0 == Some(*entity) {
-                            selected.0 = None;
+
+                        log.0.push(PlayedMove {
+                            team: Team::Red,
+                            mov: Move { start: checker.pos, jumped: place.jumped.clone(), jumps: place.jumps.clone() },
+                            replaced: Some(red_move.as_move()),
+                        });
+
+                        move_piece(&mut commands, &mut map, checker_id, checker, place.jumps.clone(), &settings);
+
+                        for pos in place.jumped.iter() {
+                            let pos = *pos;
+
+                            let target = map.get_place_checker(pos)
+                            .expect(&format!("Failed to get checker at pos {:?}", pos));
+                            let (_, checker) = checkers.get_mut(target).unwrap();
+
+                            println!("Kill piece 3!");
+                            kill_piece(&mut commands, &mut map, checker, &mut red_stack, &mut black_stack, &settings);
                         }
-                    },
+
+                        selected.0 = None;
+                    }
                 }
             },
-            _ => (),
+            _ => ()
         }
     }
 }
 
+/// F3 wins the game on the spot, for getting to the ending quickly
+pub fn debug_win(
+    input: Res<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut end_game: EventWriter<EndGameEvent>,
+) {
+    if input.just_pressed(KeyCode::F3) && state.try_set(GameState::Outro) {
+        end_game.send(EndGameEvent(Team::Red));
+    }
+}
+
 pub fn check_player_loss(
     checkers: Query<&Checker>,
     map: Res<CheckerMap>,
-    mut turn: ResMut<Turn>,
+    mut state: ResMut<State<GameState>>,
     mut end_game: EventWriter<EndGameEvent>,
 ) {
-    match *turn {
-        Turn::BlackForesight | Turn::RedCorrection => (),
-        _ => return,
-    }
-
     let mut lost = true;
     for checker in checkers.iter() {
         if checker.team == Team::Red && checker.alive {
@@ -191,7 +435,8 @@ pub fn check_player_loss(
     if lost {
         println!("Player lost the game");
         end_game.send(EndGameEvent(Team::Black));
-        *turn = Turn::PostGame;
+        // The game ending beats anything else queued this frame, like a pause
+        let _ = state.overwrite_set(GameState::Outro);
     }
 }
 
@@ -201,7 +446,9 @@ pub fn enable_valid_spaces(
     checkers: Query<(Entity, &Checker)>,
     check_map: Res<CheckerMap>,
     place_map: Res<PlaceMap>,
+    variant: Res<Variant>,
     mut header: ResMut<Header>,
+    locale: Res<Locale>,
 ) {
     let mut checkers_with_jumps = vec![];
     for (id, checker) in checkers.iter().filter(|(_, c)| c.team == Team::Red && c.alive) {
@@ -219,8 +466,8 @@ pub fn enable_valid_spaces(
 
         let (id, checker) = checkers.get(event.0).unwrap();
 
-        if checkers_with_jumps.len() > 0 && !checkers_with_jumps.contains(&id) {
-            header.0 = Cow::Borrowed("You must take any available jumps");
+        if variant.forced_captures() && checkers_with_jumps.len() > 0 && !checkers_with_jumps.contains(&id) {
+            header.0 = Cow::Owned(locale.get("header-forced-capture"));
             continue;
         }
 
@@ -228,7 +475,7 @@ pub fn enable_valid_spaces(
 
         let jumps = check_map.get_jumps(pos, Team::Red, checker.king);
 
-        if jumps.len() == 0 {
+        if jumps.len() == 0 || !variant.forced_captures() {
             let moves = check_map.get_available_moves(pos, Team::Red, checker.king);
 
             for mov in moves {
@@ -237,153 +484,120 @@ pub fn enable_valid_spaces(
                 place.jumped = vec![];
                 place.jumps = vec![mov];
             }
-        } else {
-            for (jumped, jumps) in jumps {
-                let mut place = places.get_mut(place_map.get(*jumps.last().unwrap())).unwrap();
-                place.valid = true;
-                place.jumped = jumped;
-                place.jumps = jumps;
-            }
+        }
+
+        for (jumped, jumps) in jumps {
+            let mut place = places.get_mut(place_map.get(*jumps.last().unwrap())).unwrap();
+            place.valid = true;
+            place.jumped = jumped;
+            place.jumps = jumps;
         }
     }
 }
 
 pub fn enemy_play(
     mut commands: Commands,
-    mut turn: ResMut<Turn>,
+    mut state: ResMut<State<GameState>>,
     mut checkers: Query<(Entity, &mut Checker)>,
     mut check_map: ResMut<CheckerMap>,
     mut events: EventWriter<EndGameEvent>,
     mut red_stack: ResMut<RedChipStack>,
     mut black_stack: ResMut<BlackChipStack>,
-    mut moved: ResMut<BlackHasMoved>,
+    red_move: Res<RedMove>,
+    variant: Res<Variant>,
+    ai: Res<AiConfig>,
+    mut rng: ResMut<GameRng>,
+    mut log: ResMut<GameLog>,
+    settings: Res<Settings>,
 ) {
-    if *turn != Turn::Black || moved.0 {
-        return;
-    }
-
-    // Enemy thought process:
-    // 1. Take the longest possible jump
-    // 2. If possible, approach the checker the player is moving next turn
-    // 3. If possible, king a checker
-    // 4. Move a random checker forward, prioritizing kings
+    let board = Board::from_checkers(checkers.iter_mut().map(|(_, c)| c)).with_variant(*variant);
+    let foretold = (!red_move.jumps.is_empty()).then(|| red_move.as_move());
 
-    let mut best_jump: Option<(_, Vec<IVec2>, _)> = None;
+    if let Some(mov) = choose_black_move(&board, foretold.as_ref(), &ai, &mut *rng) {
+        log.0.push(PlayedMove { team: Team::Black, mov: mov.clone(), replaced: None });
 
-    for (entity, checker) in checkers.iter_mut() {
-        if checker.team == Team::Black && checker.alive == true {
-            let jumps = check_map.get_jumps(checker.pos, Team::Black, checker.king);
-            for (hopped, hops) in jumps {
-                if best_jump.is_none() || hopped.len() > best_jump.as_ref().unwrap().1.len() {
-                    best_jump = Some((entity, hopped, hops));
-                }
-            }
-        }
-    }
-
-    if let Some((checker_id, jumped, jumps)) = best_jump {
+        let checker_id = check_map.get_place_checker(mov.start).unwrap();
         let (_, checker) = checkers.get_mut(checker_id).unwrap();
-        
-        move_piece(&mut commands, &mut check_map, checker_id, checker, jumps);
 
-        for pos in jumped {
+        move_piece(&mut commands, &mut check_map, checker_id, checker, mov.jumps, &settings);
+
+        for pos in mov.jumped {
             let hopped_checker = check_map.get_place_checker(pos).unwrap();
             let (_, checker) = checkers.get_mut(hopped_checker).unwrap();
             
-            kill_piece(&mut commands, &mut check_map, checker, &mut red_stack, &mut black_stack);
+            kill_piece(&mut commands, &mut check_map, checker, &mut red_stack, &mut black_stack, &settings);
         }
 
-        moved.0 = true;
         return;
     }
-    else {
-        // TODO: Actually pick a random checker
-        for (checker_id, checker) in checkers.iter_mut() {
-            if checker.team == Team::Black && checker.alive {
-                let moves = check_map.get_available_moves(checker.pos, Team::Black, checker.king);
-                if moves.len() > 0 {
-                    let mov = moves[rand::thread_rng().gen_range(0..moves.len())];
-
-                    move_piece(&mut commands, &mut check_map, checker_id, checker, vec![mov]);
-
-                    moved.0 = true;
-                    return;
-                }
-            }
-        }
-    }
 
     // If we get here, Black loses because they cannot move
 
     println!("Black lost the game");
     events.send(EndGameEvent(Team::Red));
-    *turn = Turn::PostGame;
+    let _ = state.overwrite_set(GameState::Outro);
 }
 
-pub struct BlackHasMoved(pub bool);
-
 pub fn animate_black_turn(
     jumpers: Query<Entity, Or<(With<QuadJump>, With<MultiQuadJump>)>>,
-    mut moved: ResMut<BlackHasMoved>,
-    mut turn: ResMut<Turn>,
+    mut state: ResMut<State<GameState>>,
 ) {
-    if *turn != Turn::Black || !moved.0 {
-        return;
-    }
-
     let mut animating = false;
     for _ in jumpers.iter() {
         animating = true;
     }
 
+    // If another change is already queued, this is tried again once the state comes back
     if !animating {
-        *turn = Turn::Red;
-        moved.0 = false;
+        state.try_set(GameState::Red);
     }
 }
 
-pub fn animate_red_turn(
+pub fn play_red_turn(
     commands: Commands,
-    mut turn: ResMut<Turn>,
-    mut red_move: ResMut<RedMove>,
+    mut state: ResMut<State<GameState>>,
+    red_move: Res<RedMove>,
     map: ResMut<CheckerMap>,
     mut checkers: Query<(Entity, &mut Checker)>,
-    jumpers: Query<Entity, Or<(With<QuadJump>, With<MultiQuadJump>)>>,
     red_stack: ResMut<RedChipStack>,
     black_stack: ResMut<BlackChipStack>,
+    mut log: ResMut<GameLog>,
+    settings: Res<Settings>,
 ) {
-    if *turn == Turn::Red {
-        if !red_move.performed {
-            let dptoc = do_player_turn_or_correction(commands, &red_move, map, &mut checkers, red_stack, black_stack);
-            if let None = dptoc {
-                *turn = Turn::RedCorrection;
-                println!("Turn invalidated");
-                return;
-            }
+    let dptoc = do_player_turn_or_correction(commands, &red_move, map, &mut checkers, red_stack, black_stack, &settings);
+    if let None = dptoc {
+        // Red has to correct the move, even over a pause queued this frame
+        let _ = state.overwrite_set(GameState::RedCorrection);
+        println!("Turn invalidated");
+        return;
+    }
 
-            red_move.performed = true;
-            return;
-        }
+    log.0.push(PlayedMove { team: Team::Red, mov: red_move.as_move(), replaced: None });
+}
 
-        let mut animating = false;
-        for _ in jumpers.iter() {
-            animating = true;
-        }
+pub fn animate_red_turn(
+    mut state: ResMut<State<GameState>>,
+    jumpers: Query<Entity, Or<(With<QuadJump>, With<MultiQuadJump>)>>,
+) {
+    let mut animating = false;
+    for _ in jumpers.iter() {
+        animating = true;
+    }
 
-        if !animating {
-            println!("Finished animating");
-            *turn = Turn::BlackForesight;
-        }
+    if !animating {
+        println!("Finished animating");
+        state.try_set(GameState::BlackForesight);
     }
 }
 
 fn do_player_turn_or_correction(
     mut commands: Commands,
-    red_move: &ResMut<RedMove>,
+    red_move: &Res<RedMove>,
     mut map: ResMut<CheckerMap>,
     checkers: &mut Query<(Entity, &mut Checker)>,
     mut red_stack: ResMut<RedChipStack>,
     mut black_stack: ResMut<BlackChipStack>,
+    settings: &Settings,
 ) -> Option<()> {
     let checker_id = map.get_place_checker(red_move.start)?;
 
@@ -404,12 +618,12 @@ fn do_player_turn_or_correction(
     if map.is_place_free(*red_move.jumps.last().unwrap()) {
         let (checker_id, checker) = checkers.get_mut(checker_id).ok()?;
 
-        move_piece(&mut commands, &mut map, checker_id, checker, red_move.jumps.clone());
+        move_piece(&mut commands, &mut map, checker_id, checker, red_move.jumps.clone(), settings);
 
         for kill in kills {
             let (_, target) = checkers.get_mut(kill).unwrap();
 
-            kill_piece(&mut commands, &mut map, target, &mut red_stack, &mut black_stack);
+            kill_piece(&mut commands, &mut map, target, &mut red_stack, &mut black_stack, settings);
         }
     } else {
         return None;
@@ -424,6 +638,7 @@ fn move_piece(
     checker_id: Entity,
     mut checker: Mut<Checker>,
     mut jumps: Vec<IVec2>,
+    settings: &Settings,
 ) {
     let dest = *jumps.last().unwrap();
 
@@ -432,12 +647,10 @@ fn move_piece(
     jumps.insert(0, checker.pos);
     commands.entity(checker_id).insert(MultiQuadJump::new(
         jumps.windows(2)
-        .map(|jumps| QuadJump::new(
-            get_checkboard_pos(jumps[0]),
-            get_checkboard_pos(jumps[1]),
-            30.0,
-            0.25 
-        ))
+        .map(|jumps| QuadJump::arc(get_checkboard_pos(jumps[0]), get_checkboard_pos(jumps[1]), HOP_HEIGHT)
+            .over(settings.animation_time(0.25))
+            .squash(LANDING_SQUASH)
+        )
         .collect()
     ));
     checker.pos = dest;
@@ -453,17 +666,20 @@ fn kill_piece(
     mut checker: Mut<Checker>,
     red_stack: &mut ResMut<RedChipStack>,
     black_stack: &mut ResMut<BlackChipStack>,
+    settings: &Settings,
 ) {
     commands.entity(map.get_place_checker(checker.pos).unwrap())
-    .insert(QuadJump::new(
+    .insert(QuadJump::arc(
         get_checkboard_pos(checker.pos),
         get_stack_pos(match checker.team {
             Team::Red => red_stack.height,
             Team::Black => black_stack.height,
         }, checker.team),
-        5.0,
-        0.5,
-    ));
+        0.1,
+    )
+    .at_speed(settings.animation_rate(0.6))
+    // Tumbles off the board, landing the right way up
+    .spin(Vec3::Z, 1.0));
     
     checker.alive = false;
     map.set_place(checker.pos, None);
@@ -481,34 +697,88 @@ pub struct Kingify(pub bool);
 pub fn make_kings(
     mut commands: Commands,
     mut checkers: Query<(Entity, &mut Checker, &Kingify)>,
-    models: Query<&CheckerMeshReference, Without<Checker>>, 
-    mut meshes: Query<&mut Visibility, Without<Checker>>, 
 ) {
     for (id, mut checker, kingify) in checkers.iter_mut() {
         checker.king = kingify.0;
-        match kingify.0 {
-            true => {
-                meshes.get_mut(models.get(checker.model).unwrap().0).unwrap().is_visible = false;
-                meshes.get_mut(models.get(checker.king_model).unwrap().0).unwrap().is_visible = true;
-            },
-            false => {
-                meshes.get_mut(models.get(checker.model).unwrap().0).unwrap().is_visible = true;
-                meshes.get_mut(models.get(checker.king_model).unwrap().0).unwrap().is_visible = false;
-            }
-        }
         commands.entity(id).remove::<Kingify>();
     }
 }
 
-pub fn play_checker_noises(
-    audio: Res<Audio>,
-    click: Res<ClickNoise>,
-    mut events: EventReader<QuadLandEvent>,
-    checkers: Query<&Checker>
+/// Swaps between a checker's regular and king model to match whether it's a king
+pub fn show_kings(
+    checkers: Query<(&Checker, &CheckerModels), Changed<Checker>>,
+    models: Query<&CheckerMeshReference, Without<Checker>>, 
+    mut meshes: Query<&mut Visibility, Without<Checker>>, 
 ) {
-    for landing in events.iter() {
-        if let Ok(_) = checkers.get(landing.0) {
-            audio.play(click.0.as_ref().unwrap().clone());
+    for (checker, checker_models) in checkers.iter() {
+        // The models' meshes only show up once their scenes have spawned
+        let (model, king_model) = match (models.get(checker_models.model), models.get(checker_models.king_model)) {
+            (Ok(model), Ok(king_model)) => (model.0, king_model.0),
+            _ => continue,
+        };
+
+        meshes.get_mut(model).unwrap().is_visible = !checker.king;
+        meshes.get_mut(king_model).unwrap().is_visible = checker.king;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(pieces: &[(IVec2, Team)], variant: Variant) -> Board {
+        let checkers: Vec<_> = pieces.iter()
+        .map(|(pos, team)| Checker { team: *team, pos: *pos, alive: true, king: false })
+        .collect();
+
+        Board::from_checkers(checkers.iter()).with_variant(variant)
+    }
+
+    fn typed(mov: &[IVec2], sep: &str) -> String {
+        mov.iter().map(|p| square_number(*p).to_string()).collect::<Vec<_>>().join(sep)
+    }
+
+    #[test]
+    fn typed_hops_pick_between_jumps_that_end_together() {
+        // Two double jumps from the same checker, one up each side, land on the same place
+        let board = board(&[
+            (ivec2(2, 2), Team::Red),
+            (ivec2(3, 3), Team::Black),
+            (ivec2(3, 5), Team::Black),
+            (ivec2(1, 3), Team::Black),
+            (ivec2(1, 5), Team::Black),
+        ], Variant::Standard);
+
+        for (via, jumped) in [(ivec2(4, 4), [ivec2(3, 3), ivec2(3, 5)]), (ivec2(0, 4), [ivec2(1, 3), ivec2(1, 5)])] {
+            let mov = find_typed_move(&typed(&[ivec2(2, 2), via, ivec2(2, 6)], "x"), &board).unwrap();
+
+            assert_eq!(mov.jumps, vec![via, ivec2(2, 6)]);
+            assert_eq!(mov.jumped, jumped.to_vec());
+        }
+
+        // Leaving the hops out takes either
+        assert_eq!(find_typed_move(&typed(&[ivec2(2, 2), ivec2(2, 6)], "X"), &board).unwrap().dest(), ivec2(2, 6));
+    }
+
+    #[test]
+    fn typed_step_is_refused_while_a_capture_is_forced() {
+        let pieces = [
+            (ivec2(2, 2), Team::Red),
+            (ivec2(3, 3), Team::Black),
+            (ivec2(6, 2), Team::Red),
+        ];
+        let step = typed(&[ivec2(6, 2), ivec2(7, 3)], "-");
+
+        assert_eq!(find_typed_move(&step, &board(&pieces, Variant::Standard)), Err("header-forced-capture"));
+        assert!(find_typed_move(&step, &board(&pieces, Variant::OptionalCaptures)).is_ok());
+    }
+
+    #[test]
+    fn typed_nonsense_is_not_a_move() {
+        let board = board(&[(ivec2(2, 2), Team::Red), (ivec2(5, 5), Team::Black)], Variant::Standard);
+
+        for text in ["", "hello", "1-2", &typed(&[ivec2(5, 5), ivec2(4, 4)], "-"), &typed(&[ivec2(2, 2), ivec2(2, 4)], "-")] {
+            assert_eq!(find_typed_move(text, &board), Err("header-not-a-move"), "{:?}", text);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+/// How many past lines the log keeps
+const LOG_LENGTH: usize = 100;
+/// Typing clicks play every this many characters, so quick text doesn't turn into a buzz
+const CHARS_PER_CLICK: usize = 2;
+/// Audio channel the typing clicks play on, so they can be kept quieter than other sounds
+pub const TYPING_CHANNEL: &str = "typing";
+
+/// What the opponent is saying. Lines are typed out a character at a time, and kept in a log once said.
+pub struct ScreenText {
+    text: String,
+    /// How many characters of `text` are showing
+    revealed: f32,
+    pub alpha: f32,
+    pub colour: [f32; 3],
+    /// Every line said so far, oldest first
+    pub log: Vec<(String, [f32; 3])>,
+}
+
+impl Default for ScreenText {
+    fn default() -> Self {
+        ScreenText {
+            text: String::new(),
+            revealed: 0.0,
+            alpha: 1.0,
+            colour: DEFAULT_TEXT_COLOUR,
+            log: vec![],
+        }
+    }
+}
+
+impl ScreenText {
+    /// Replaces whatever is on screen
+    pub fn say(&mut self, line: &str, colour: [f32; 3]) {
+        self.clear();
+        self.alpha = 1.0;
+        self.colour = colour;
+        self.add_line(line);
+    }
+
+    /// Adds to the end of the last line
+    pub fn continue_line(&mut self, line: &str) {
+        if self.text.is_empty() {
+            self.add_line(line);
+            return;
+        }
+
+        self.text.push(' ');
+        self.text.push_str(line);
+
+        if let Some((last, _)) = self.log.last_mut() {
+            last.push(' ');
+            last.push_str(line);
+        }
+    }
+
+    /// Adds a line below what's on screen
+    pub fn add_line(&mut self, line: &str) {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(line);
+
+        self.log.push((line.to_string(), self.colour));
+        if self.log.len() > LOG_LENGTH {
+            self.log.remove(0);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.revealed = 0.0;
+    }
+
+    /// Empties the screen ready for a new script, keeping the log
+    pub fn reset(&mut self) {
+        self.clear();
+        self.alpha = 1.0;
+        self.colour = DEFAULT_TEXT_COLOUR;
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.revealed as usize >= self.text.chars().count()
+    }
+
+    /// Shows the rest of the text straight away
+    pub fn complete(&mut self) {
+        self.revealed = self.text.chars().count() as f32;
+    }
+
+    /// The part of the text typed out so far
+    pub fn shown(&self) -> &str {
+        match self.text.char_indices().nth(self.revealed as usize) {
+            Some((i, _)) => &self.text[..i],
+            None => &self.text,
+        }
+    }
+}
+
+/// Types out a little more of the text each frame, clicking along if the player wants
+pub fn type_screen_text(
+    mut text: ResMut<ScreenText>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    if text.is_complete() {
+        return;
+    }
+
+    let before = text.revealed as usize;
+    text.revealed += settings.type_speed * settings.text_speed * time.delta_seconds();
+    let after = text.revealed as usize;
+
+    let typed_letter = text.text.chars().skip(before).take(after - before).any(|c| !c.is_whitespace());
+
+    if settings.typing_sounds && typed_letter && before / CHARS_PER_CLICK != after / CHARS_PER_CLICK {
+        sfx.send(Sfx::Type.into());
+    }
+}
+
+pub fn screen_text_display(
+    mut ctx: ResMut<EguiContext>,
+    text: Res<ScreenText>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    egui::Area::new("SpeechText")
+    .anchor(egui::Align2::LEFT_TOP, (0., 0.))
+    .movable(false)
+    .interactable(false)
+    .show(ctx, |ui| {
+        ui.centered_and_justified(|ui| {
+            let color = egui::Rgba::from_rgba_unmultiplied(text.colour[0], text.colour[1], text.colour[2], text.alpha);
+            let text = egui::RichText::new(text.shown()).color(color);
+            ui.heading(text);
+        })
+    });
+}
+
+/// L shows or hides everything the opponent has said
+pub fn text_log_display(
+    mut ctx: ResMut<EguiContext>,
+    text: Res<ScreenText>,
+    input: Res<Input<KeyCode>>,
+    locale: Res<Locale>,
+    mut open: Local<bool>,
+) {
+    if input.just_pressed(KeyCode::L) {
+        *open = !*open;
+    }
+
+    egui::Window::new(locale.get("text-log"))
+    .id(egui::Id::new("TextLog"))
+    .open(&mut open)
+    .collapsible(false)
+    .show(ctx.ctx_mut(), |ui| {
+        egui::ScrollArea::vertical()
+        .show(ui, |ui| {
+            for (line, colour) in text.log.iter() {
+                let color = egui::Rgba::from_rgb(colour[0], colour[1], colour[2]);
+                ui.label(egui::RichText::new(line).color(color));
+            }
+        });
+    });
+}
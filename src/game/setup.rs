@@ -3,15 +3,6 @@ use super::*;
 #[derive(Component)]
 pub struct PlayerCamera;
 
-pub struct ClickNoise(pub Option<Handle<AudioSource>>);
-
-pub fn load_audio(
-    mut click: ResMut<ClickNoise>,
-    assets: Res<AssetServer>,
-) {
-    click.0 = Some(assets.load("sounds/click.wav")); 
-}
-
 pub fn set_up_camera(mut commands: Commands) {
     // Ended up messing around with projections and scooching thing around to get bevy_mod_raycast to actually pick up things that were apparently too close to the camera.
     let perspective_projection = PerspectiveProjection {
@@ -37,10 +28,7 @@ pub fn set_up_camera(mut commands: Commands) {
     })
     // Delay makes movement + picking not work for a bit, while the table builds
     .with_delay(4.0, |c| {
-        c
-        .insert(LerpToTarget::default())
-        .insert(SlerpToTarget::default())
-        .insert_bundle(PickingCameraBundle::default());
+        c.insert_bundle(PickingCameraBundle::default());
     })
     .insert(Name::new("Camera"))
     .insert(PlayerCamera);
@@ -85,39 +73,35 @@ pub struct SetupModel(bool, bool);
 
 pub struct Table(pub Option<Entity>);
 
-pub fn set_up_checkerboard(
+#[derive(Component)]
+pub struct Checkboard;
+
+/// The visible models of a checker, swapped between when it becomes a king
+#[derive(Component)]
+pub struct CheckerModels {
+    pub model: Entity,
+    pub king_model: Entity,
+}
+
+/// Spawns the table, the places on the board and the checkers, with no models attached so it works headless
+pub fn set_up_board(
     mut commands: Commands, 
-    assets: Res<AssetServer>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    // mut materials: ResMut<Assets<StandardMaterial>>,
     mut check_map: ResMut<CheckerMap>,
     mut place_map: ResMut<PlaceMap>,
     mut table_res: ResMut<Table>,
 ) {
-    let table = assets.load("models/table.glb#Scene0");
-    let checkboard = assets.load("models/checkboard.glb#Scene0");
-    let black_checker = assets.load("models/check_black.glb#Scene0");
-    let red_checker = assets.load("models/check_red.glb#Scene0");
-    let black_king = assets.load("models/check_black_king.glb#Scene0");
-    let red_king = assets.load("models/check_red_king.glb#Scene0");
-
-    let plane_mesh = meshes.add(shape::Plane { size: 0.061 }.into());
-
     let table = commands
     .spawn()
     .insert(Name::new("Table"))
     .insert(Transform::from_xyz(0., -1., 0.))
     .insert(GlobalTransform::default())
     .with_children(|b| {
-        b.spawn_scene(table);
-
         b.spawn()
         .insert(Name::new("Checkboard"))
+        .insert(Checkboard)
         .insert(Transform::from_xyz(0., 0.06, 0.))
         .insert(GlobalTransform::default())
         .with_children(|b| {
-            b.spawn_scene(checkboard);
-
             for i in 0..64 {
                 let coord = index_checkboard(i);
                 let pos = get_checkboard_pos(coord);
@@ -127,13 +111,6 @@ pub fn set_up_checkerboard(
                 .insert(CheckerPlace { pos: coord, jumps: vec![], jumped: vec![], valid: false })
                 .insert(Transform::from_xyz(pos.x, 0.025, pos.z))
                 .insert(GlobalTransform::default())
-                .insert(plane_mesh.clone())
-                // .insert(place_mat.clone())
-                // Visibility is part of bevy_mod_raycast's criteria for picking things for some reason.
-                // Using it to enable/disable picking
-                .insert(Visibility::default())
-                .insert(ComputedVisibility::default())
-                .insert_bundle(PickableBundle::default())
                 .id();
 
                 place_map.insert(coord, place);
@@ -148,30 +125,12 @@ pub fn set_up_checkerboard(
 
         let initial_coord = get_initial_checkboard_coord(i, Team::Black);
         let initial_pos = get_checkboard_pos(initial_coord);
-        
-        let model = commands.spawn()
-        .insert(Name::new("Model"))
-        .insert(SetupModel(false, false))
-        .insert(Transform::default())
-        .insert(GlobalTransform::default())
-        .with_children(|b| {
-            b.spawn_scene(black_checker.clone());
-        }).id();
-        let king = commands.spawn()
-        .insert(Name::new("KingModel"))
-        .insert(SetupModel(false, true))
-        .insert(Transform::default())
-        .insert(GlobalTransform::default())
-        .with_children(|b| {
-            b.spawn_scene(black_king.clone());
-        }).id();
 
         let checker = commands.spawn()
         .insert(Name::new(format!("BlackChecker{}", i)))
-        .insert(Checker { team: Team::Black, pos: initial_coord, alive: true, king: false, model, king_model: king })
+        .insert(Checker { team: Team::Black, pos: initial_coord, alive: true, king: false })
         .insert(Transform::from_xyz(initial_pos.x, y, initial_pos.z))
         .insert(GlobalTransform::default())
-        .push_children(&[model, king])
         .id();
         
         check_map.set_place(initial_coord, Some((checker, Team::Black)));
@@ -179,32 +138,87 @@ pub fn set_up_checkerboard(
         let initial_coord = get_initial_checkboard_coord(i, Team::Red);
         let initial_pos = get_checkboard_pos(initial_coord);
 
+        let checker = commands.spawn()
+        .insert(Name::new(format!("RedChecker{}", i)))
+        .insert(Checker { team: Team::Red, pos: initial_coord, alive: true, king: false })
+        .insert(Transform::from_xyz(initial_pos.x, y, initial_pos.z).with_rotation(Quat::from_rotation_y(PI)))
+        .insert(GlobalTransform::default())
+        .id();
+        
+        check_map.set_place(initial_coord, Some((checker, Team::Red)));
+    }
+}
+
+/// Gives the board spawned by `set_up_board` its models, and makes the places pickable
+pub fn set_up_checkerboard(
+    mut commands: Commands, 
+    assets: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    highlights: Res<HighlightAssets>,
+    table_res: Res<Table>,
+    checkboards: Query<Entity, With<Checkboard>>,
+    places: Query<Entity, With<CheckerPlace>>,
+    checkers: Query<(Entity, &Checker)>,
+) {
+    let table = assets.load("models/table.glb#Scene0");
+    let checkboard = assets.load("models/checkboard.glb#Scene0");
+    let black_checker = assets.load("models/check_black.glb#Scene0");
+    let red_checker = assets.load("models/check_red.glb#Scene0");
+    let black_king = assets.load("models/check_black_king.glb#Scene0");
+    let red_king = assets.load("models/check_red_king.glb#Scene0");
+
+    let plane_mesh = meshes.add(shape::Plane { size: 0.061 }.into());
+
+    commands.entity(table_res.0.unwrap())
+    .with_children(|b| {
+        b.spawn_scene(table);
+    });
+
+    for board in checkboards.iter() {
+        commands.entity(board)
+        .with_children(|b| {
+            b.spawn_scene(checkboard.clone());
+        });
+    }
+
+    for place in places.iter() {
+        commands.entity(place)
+        .insert(plane_mesh.clone())
+        .insert(highlights.place.clone())
+        // Visibility is part of bevy_mod_raycast's criteria for picking things for some reason.
+        // Using it to enable/disable picking
+        .insert(Visibility::default())
+        .insert(ComputedVisibility::default())
+        .insert_bundle(PickableBundle::default());
+    }
+
+    for (id, checker) in checkers.iter() {
+        let red = checker.team == Team::Red;
+        let (checker_scene, king_scene) = match checker.team {
+            Team::Black => (black_checker.clone(), black_king.clone()),
+            Team::Red => (red_checker.clone(), red_king.clone()),
+        };
+
         let model = commands.spawn()
         .insert(Name::new("Model"))
-        .insert(SetupModel(true, false))
+        .insert(SetupModel(red, false))
         .insert(Transform::default())
         .insert(GlobalTransform::default())
         .with_children(|b| {
-            b.spawn_scene(red_checker.clone());
+            b.spawn_scene(checker_scene);
         }).id();
         let king = commands.spawn()
         .insert(Name::new("KingModel"))
-        .insert(SetupModel(true, true))
+        .insert(SetupModel(red, true))
         .insert(Transform::default())
         .insert(GlobalTransform::default())
         .with_children(|b| {
-            b.spawn_scene(red_king.clone());
+            b.spawn_scene(king_scene);
         }).id();
 
-        let checker = commands.spawn()
-        .insert(Name::new(format!("RedChecker{}", i)))
-        .insert(Checker { team: Team::Red, pos: initial_coord, alive: true, king: false, model, king_model: king })
-        .insert(Transform::from_xyz(initial_pos.x, y, initial_pos.z).with_rotation(Quat::from_rotation_y(PI)))
-        .insert(GlobalTransform::default())
-        .push_children(&[model, king])
-        .id();
-        
-        check_map.set_place(initial_coord, Some((checker, Team::Red)));
+        commands.entity(id)
+        .insert(CheckerModels { model, king_model: king })
+        .push_children(&[model, king]);
     }
 }
 
@@ -0,0 +1,329 @@
+use super::*;
+use std::collections::HashMap;
+
+/// How far either way a sound's pitch can be pushed, so repeats don't sound identical
+const PITCH_VARIATION: f32 = 0.08;
+/// Sound effects each take a free one of these, since pitch, panning and volume can only be set for a whole channel
+const SFX_CHANNELS: [&str; 8] = ["sfx-0", "sfx-1", "sfx-2", "sfx-3", "sfx-4", "sfx-5", "sfx-6", "sfx-7"];
+/// Music crossfades from one of these to the other
+const MUSIC_CHANNELS: [&str; 2] = ["music-0", "music-1"];
+const CROSSFADE_SECS: f32 = 2.0;
+/// Clicking along with every few letters gets grating at full volume
+const TYPING_VOLUME: f32 = 0.3;
+/// How far from the camera a sound plays at full volume. Anything further is quieter.
+const FULL_VOLUME_DISTANCE: f32 = 1.3;
+/// How far towards either ear a sound can be panned, out of 0.5
+const PAN_WIDTH: f32 = 0.4;
+/// Which of `GameRng`'s substreams picks takes and pitches
+const SFX_STREAM: u64 = 2;
+
+/// Something that makes a noise
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Sfx {
+    Move,
+    /// Each landing of a multi-jump but the last
+    Hop,
+    Capture,
+    King,
+    /// Black making the foretold move impossible
+    Invalidated,
+    Win,
+    Lose,
+    /// A letter of dialogue being typed out
+    Type,
+}
+
+impl Sfx {
+    /// About how long the longest take lasts at the lowest pitch, to know when its channel is free again
+    fn secs(self) -> f32 {
+        let longest = match self {
+            Sfx::Move => 0.07,
+            Sfx::Hop => 0.04,
+            Sfx::Capture => 0.25,
+            Sfx::King => 0.6,
+            Sfx::Invalidated => 0.4,
+            Sfx::Win => 0.72,
+            Sfx::Lose => 1.0,
+            Sfx::Type => 0.05,
+        };
+        longest / (1.0 - PITCH_VARIATION)
+    }
+}
+
+const SFX_FILES: [(Sfx, &[&str]); 8] = [
+    (Sfx::Move, &["sounds/click.wav", "sounds/click_soft.wav"]),
+    (Sfx::Hop, &["sounds/hop.wav"]),
+    (Sfx::Capture, &["sounds/capture.wav"]),
+    (Sfx::King, &["sounds/king.wav"]),
+    (Sfx::Invalidated, &["sounds/invalidated.wav"]),
+    (Sfx::Win, &["sounds/win.wav"]),
+    (Sfx::Lose, &["sounds/lose.wav"]),
+    (Sfx::Type, &["sounds/click_soft.wav"]),
+];
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Music {
+    Menu,
+    Game,
+}
+
+const MUSIC_FILES: [(Music, &str); 2] = [
+    (Music::Menu, "sounds/music_menu.wav"),
+    (Music::Game, "sounds/music_game.wav"),
+];
+
+pub struct SfxEvent {
+    pub sfx: Sfx,
+    /// Where the sound came from, to pan and quieten it by. Sounds from nowhere in particular play centred.
+    pub at: Option<Vec3>,
+}
+
+impl SfxEvent {
+    pub fn at(sfx: Sfx, at: Vec3) -> Self {
+        SfxEvent { sfx, at: Some(at) }
+    }
+}
+
+impl From<Sfx> for SfxEvent {
+    fn from(sfx: Sfx) -> Self {
+        SfxEvent { sfx, at: None }
+    }
+}
+
+/// Every sound the game makes. Effects can have several takes, one of which is picked each time.
+pub struct SoundBank {
+    sfx: HashMap<Sfx, Vec<Handle<AudioSource>>>,
+    music: HashMap<Music, Handle<AudioSource>>,
+}
+
+pub fn load_sounds(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.insert_resource(SoundBank {
+        sfx: SFX_FILES.iter()
+        .map(|(sfx, files)| (*sfx, files.iter().map(|f| assets.load(*f)).collect()))
+        .collect(),
+        music: MUSIC_FILES.iter()
+        .map(|(music, file)| (*music, assets.load(*file)))
+        .collect(),
+    });
+}
+
+/// Panning from 0 for the left ear to 1 for the right, and how much to quieten a sound by, from where it is
+/// relative to the camera. Sides are as they're seen on screen.
+fn spatialize(at: Vec3, camera: &Transform) -> (f32, f32) {
+    let relative = camera.rotation.inverse() * (at - camera.translation);
+    let distance = relative.length();
+
+    let panning = 0.5 + PAN_WIDTH * relative.normalize_or_zero().x;
+    let attenuation = if distance > FULL_VOLUME_DISTANCE { FULL_VOLUME_DISTANCE / distance } else { 1.0 };
+
+    (panning, attenuation)
+}
+
+/// Plays each sound effect asked for, with a random take and pitch, from where it happened
+pub fn play_sfx(
+    mut events: EventReader<SfxEvent>,
+    bank: Res<SoundBank>,
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+    time: Res<Time>,
+    mut busy_until: Local<[f64; SFX_CHANNELS.len()]>,
+    game_rng: Res<GameRng>,
+    mut sfx_rng: Local<SubRng>,
+) {
+    let rng = sfx_rng.get(&game_rng, SFX_STREAM);
+    let now = time.seconds_since_startup();
+
+    for SfxEvent { sfx, at } in events.iter() {
+        let sound = match bank.sfx.get(sfx).and_then(|takes| takes.choose(&mut *rng)) {
+            Some(sound) => sound.clone(),
+            None => continue,
+        };
+
+        // Changing a channel's panning would move whatever's still playing on it, so a sound that can't have a
+        // channel to itself is left out
+        let channel = match sfx {
+            Sfx::Type => TYPING_CHANNEL,
+            _ => match busy_until.iter().position(|until| *until <= now) {
+                Some(free) => {
+                    busy_until[free] = now + sfx.secs() as f64;
+                    SFX_CHANNELS[free]
+                },
+                None => continue,
+            },
+        };
+        let channel = AudioChannel::new(channel.to_string());
+
+        if *sfx != Sfx::Type {
+            let (panning, attenuation) = match (at, camera.get_single()) {
+                (Some(at), Ok(camera)) => spatialize(*at, camera),
+                _ => (0.5, 1.0),
+            };
+
+            audio.set_panning_in_channel(panning, &channel);
+            audio.set_volume_in_channel(settings.volume * settings.sfx_volume * attenuation, &channel);
+        }
+
+        audio.set_playback_rate_in_channel(1.0 + rng.gen_range(-PITCH_VARIATION..=PITCH_VARIATION), &channel);
+        audio.play_in_channel(sound, &channel);
+    }
+}
+
+/// Which track is playing, and how far the crossfade to it from the last one has got
+pub struct MusicPlayer {
+    current: Option<Music>,
+    /// Which of `MUSIC_CHANNELS` the current track is on
+    channel: usize,
+    /// From 0 for all the last track, to 1 for all the current one
+    fade: f32,
+}
+
+impl Default for MusicPlayer {
+    fn default() -> Self {
+        MusicPlayer { current: None, channel: 0, fade: 1.0 }
+    }
+}
+
+/// Crossfades to the track for wherever the player is. The endings have no music.
+pub fn play_music(
+    state: Res<State<GameState>>,
+    mut player: ResMut<MusicPlayer>,
+    bank: Res<SoundBank>,
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let wanted = match state.current() {
+        GameState::Menu | GameState::Intro => Some(Music::Menu),
+        GameState::Outro => None,
+        GameState::Paused => player.current,
+        _ => Some(Music::Game),
+    };
+
+    if wanted != player.current {
+        // The old track keeps fading out on its channel while the new one comes in on the other
+        player.channel = 1 - player.channel;
+        let channel = AudioChannel::new(MUSIC_CHANNELS[player.channel].to_string());
+
+        audio.stop_channel(&channel);
+        audio.set_volume_in_channel(0.0, &channel);
+        if let Some(music) = wanted.and_then(|m| bank.music.get(&m)) {
+            audio.play_looped_in_channel(music.clone(), &channel);
+        }
+
+        player.current = wanted;
+        player.fade = 0.0;
+    } else if player.fade >= 1.0 && !settings.is_changed() {
+        return;
+    }
+
+    player.fade = (player.fade + time.delta_seconds() / CROSSFADE_SECS).min(1.0);
+
+    let volume = settings.volume * settings.music_volume;
+    let current = AudioChannel::new(MUSIC_CHANNELS[player.channel].to_string());
+    let last = AudioChannel::new(MUSIC_CHANNELS[1 - player.channel].to_string());
+
+    audio.set_volume_in_channel(volume * player.fade, &current);
+    audio.set_volume_in_channel(volume * (1.0 - player.fade), &last);
+
+    if player.fade >= 1.0 {
+        audio.stop_channel(&last);
+    }
+}
+
+/// Sets the typing channel to the master and effects volumes. Other effects set theirs as they play, and music as
+/// it fades.
+pub fn apply_volume(settings: Res<Settings>, audio: Res<Audio>) {
+    if settings.is_changed() {
+        let volume = settings.volume * settings.sfx_volume * TYPING_VOLUME;
+        audio.set_volume_in_channel(volume, &AudioChannel::new(TYPING_CHANNEL.to_string()));
+    }
+}
+
+/// Clicks for checkers landing: a move, a hop partway through a multi-jump, or a capture landing on its stack
+pub fn play_checker_noises(
+    mut events: EventReader<QuadLandEvent>,
+    // Not `GlobalTransform`, which still has where the checker was last frame
+    checkers: Query<(&Checker, &Transform, Option<&MultiQuadJump>)>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    for landing in events.iter() {
+        let (checker, tf, multi) = match checkers.get(landing.0) {
+            Ok(landed) => landed,
+            Err(_) => continue,
+        };
+
+        let noise = match (checker.alive, multi) {
+            (false, _) => Sfx::Capture,
+            (true, Some(multi)) if !multi.is_finished() => Sfx::Hop,
+            (true, _) => Sfx::Move,
+        };
+        sfx.send(SfxEvent::at(noise, tf.translation));
+    }
+}
+
+/// Has to run before `make_kings` takes the `Kingify` away
+pub fn play_king_noises(
+    checkers: Query<(&Checker, &Transform, &Kingify), Added<Kingify>>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    for (checker, tf, kingify) in checkers.iter() {
+        if kingify.0 && !checker.king {
+            sfx.send(SfxEvent::at(Sfx::King, tf.translation));
+        }
+    }
+}
+
+pub fn play_invalidated_noise(mut sfx: EventWriter<SfxEvent>) {
+    sfx.send(Sfx::Invalidated.into());
+}
+
+pub fn play_end_game_noises(
+    mut events: EventReader<EndGameEvent>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    for e in events.iter() {
+        sfx.send(match e.0 {
+            Team::Red => Sfx::Win,
+            Team::Black => Sfx::Lose,
+        }.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::playing::{LEAN_BACK_ANGLE, LEAN_BACK_POS};
+
+    fn leaning_back() -> Transform {
+        Transform::from_translation(LEAN_BACK_POS.into()).with_rotation(Quat::from_scaled_axis(LEAN_BACK_ANGLE.into()))
+    }
+
+    #[test]
+    fn stacks_sound_from_the_side_they_are_seen_on() {
+        let camera = leaning_back();
+        // Looking across the board from Red's side turns the world around, so Black's stack is on the right
+        assert!((camera.rotation * Vec3::X).x < 0.0);
+
+        let (black, _) = spatialize(get_stack_pos(0, Team::Black), &camera);
+        let (red, _) = spatialize(get_stack_pos(0, Team::Red), &camera);
+
+        assert!(black > 0.5, "{}", black);
+        assert!(red < 0.5, "{}", red);
+        assert!((black - 0.5 - (0.5 - red)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn straight_ahead_is_centred_and_far_away_is_quieter() {
+        let camera = leaning_back();
+        let ahead = camera.translation + camera.rotation * -Vec3::Z;
+
+        let (panning, attenuation) = spatialize(ahead, &camera);
+        assert!((panning - 0.5).abs() < 1e-4);
+        assert_eq!(attenuation, 1.0);
+
+        let far = camera.translation + camera.rotation * -Vec3::Z * FULL_VOLUME_DISTANCE * 4.0;
+        let (_, attenuation) = spatialize(far, &camera);
+        assert!((attenuation - 0.25).abs() < 1e-4);
+    }
+}
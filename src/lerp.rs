@@ -1,25 +1,124 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, ecs::system::Command};
 
-#[derive(Component, Default)]
+/// How close a `LerpToTarget` has to get to its target to count as there
+const TRANSLATION_TOLERANCE: f32 = 0.001;
+/// How close, in radians, a `SlerpToTarget` has to get to its target to count as there
+const ROTATION_TOLERANCE: f32 = 0.001;
+
+/// How far to move towards a target this frame. Closes the same share of the gap per second at any frame rate, and
+/// never overshoots.
+fn smoothing(ratio: f32, dt: f32) -> f32 {
+    1.0 - (-ratio * dt).exp()
+}
+
+/// Sent when a `LerpToTarget` or `SlerpToTarget` arrives, just before it's removed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReachedTargetEvent {
+    Translation(Entity),
+    Rotation(Entity),
+}
+
+/// Removes a `LerpToTarget` or `SlerpToTarget` that's arrived, unless a new one has replaced it since. Otherwise a
+/// target set on the frame the last one arrived would be thrown away with it.
+struct RemoveArrived<T: Component + PartialEq> {
+    entity: Entity,
+    arrived: T,
+}
+
+impl<T: Component + PartialEq> Command for RemoveArrived<T> {
+    fn write(self, world: &mut World) {
+        if let Some(mut entity) = world.get_entity_mut(self.entity) {
+            if entity.get::<T>() == Some(&self.arrived) {
+                entity.remove::<T>();
+            }
+        }
+    }
+}
+
+/// Eases towards `target`, closing the gap faster the higher `ratio` is, then removes itself
+#[derive(Component, Default, Clone, Copy, PartialEq)]
 pub struct LerpToTarget {
     pub ratio: f32,
     pub target: Vec3,
 }
 
-pub fn lerp_to_targets(mut query: Query<(&mut Transform, &LerpToTarget)>, time: Res<Time>) {
-    for (mut tf, lrp) in query.iter_mut() {
-        tf.translation = tf.translation.lerp(lrp.target, lrp.ratio * time.delta_seconds());
+pub fn lerp_to_targets(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &LerpToTarget)>,
+    mut reached: EventWriter<ReachedTargetEvent>,
+    time: Res<Time>,
+) {
+    for (id, mut tf, lrp) in query.iter_mut() {
+        tf.translation = tf.translation.lerp(lrp.target, smoothing(lrp.ratio, time.delta_seconds()));
+
+        if tf.translation.distance(lrp.target) <= TRANSLATION_TOLERANCE {
+            tf.translation = lrp.target;
+            commands.add(RemoveArrived { entity: id, arrived: *lrp });
+            reached.send(ReachedTargetEvent::Translation(id));
+        }
     }
 }
 
-#[derive(Component, Default)]
+/// Like `LerpToTarget`, for rotation
+#[derive(Component, Default, Clone, Copy, PartialEq)]
 pub struct SlerpToTarget {
     pub ratio: f32,
     pub target: Quat,
 }
 
-pub fn slerp_to_targets(mut query: Query<(&mut Transform, &SlerpToTarget)>, time: Res<Time>) {
-    for (mut tf, slrp) in query.iter_mut() {
-        tf.rotation = tf.rotation.slerp(slrp.target, slrp.ratio * time.delta_seconds());
+pub fn slerp_to_targets(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &SlerpToTarget)>,
+    mut reached: EventWriter<ReachedTargetEvent>,
+    time: Res<Time>,
+) {
+    for (id, mut tf, slrp) in query.iter_mut() {
+        tf.rotation = tf.rotation.slerp(slrp.target, smoothing(slrp.ratio, time.delta_seconds()));
+
+        if tf.rotation.angle_between(slrp.target) <= ROTATION_TOLERANCE {
+            tf.rotation = slrp.target;
+            commands.add(RemoveArrived { entity: id, arrived: *slrp });
+            reached.send(ReachedTargetEvent::Rotation(id));
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothing_is_the_same_at_any_frame_rate() {
+        let (ratio, target) = (3.0, 10.0);
+
+        let once = target * smoothing(ratio, 1.0);
+        let mut stepped = 0.0;
+        for _ in 0..60 {
+            stepped += (target - stepped) * smoothing(ratio, 1.0 / 60.0);
+        }
+
+        assert!((once - stepped).abs() < 1e-3, "{} vs {}", once, stepped);
+    }
+
+    #[test]
+    fn smoothing_never_overshoots() {
+        for dt in [0.0, 1.0, 100.0, 1e6, f32::INFINITY] {
+            let t = smoothing(10.0, dt);
+            assert!((0.0..=1.0).contains(&t), "{} for {}", t, dt);
+        }
+    }
+
+    #[test]
+    fn a_target_replaced_on_arrival_is_kept() {
+        let mut world = World::new();
+        let arrived = LerpToTarget { ratio: 1.0, target: Vec3::X };
+        let replaced = LerpToTarget { ratio: 1.0, target: Vec3::Y };
+
+        let entity = world.spawn().insert(replaced).id();
+        RemoveArrived { entity, arrived }.write(&mut world);
+        assert!(world.get::<LerpToTarget>(entity) == Some(&replaced));
+
+        RemoveArrived { entity, arrived: replaced }.write(&mut world);
+        assert!(world.get::<LerpToTarget>(entity).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::math::*;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+use fluent_bundle::FluentArgs;
+use bevy_kira_audio::{Audio, AudioChannel, AudioSource};
+use bevy_mod_picking::*;
+use std::borrow::Cow;
+
+pub mod flicker;
+pub mod game;
+pub mod lerp;
+pub mod locale;
+pub mod plugins;
+pub mod quadratic;
+pub mod rng;
+pub mod settings;
+pub mod stare;
+pub mod timer;
+pub mod tween;
+
+use flicker::*;
+use game::*;
+use game::ai::*;
+use game::commentary::*;
+use game::controls::*;
+use game::dialogue::*;
+use game::highlight::*;
+use game::history::*;
+use game::intro::*;
+use game::menu::*;
+use game::notation::*;
+use game::outro::*;
+use game::pause::*;
+use game::playing::*;
+use game::screen_text::*;
+use game::setup::*;
+use game::sound::*;
+use lerp::*;
+use locale::*;
+use quadratic::*;
+use rng::*;
+use settings::*;
+use stare::*;
+use timer::*;
+use tween::*;
+
+
+/*
+Notes:
+
+A game of checkers where the opponent can read your next move and react to it preemptively. You can win because they will heavily prioritize direct responses to your incoming moves,
+and not plan ahead at all. Because I don't think I have enough time to write good checkers AI
+
+_________________________
+\      ___________      /
+ \    /           \    /
+  \  /             \  /
+   \                 /
+    \               /
+     \  |       |  /
+      \ |       | /
+       \         /
+        \       /
+         \     /
+          \   /
+           \ /
+            
+
+*/
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use crate::settings::Settings;
+
+/// Every language the game has been translated into, by code and by its own name for itself
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("fr", "Français")];
+
+/// Used for anything a translation is missing
+const FALLBACK_LANGUAGE: &str = "en";
+
+fn source(language: &str) -> Option<&'static str> {
+    match language {
+        "en" => Some(include_str!("../assets/locales/en.ftl")),
+        "fr" => Some(include_str!("../assets/locales/fr.ftl")),
+        _ => None,
+    }
+}
+
+/// Player-facing text in the chosen language, from the Fluent files in `assets/locales`
+pub struct Locale {
+    language: String,
+    bundle: Option<FluentBundle<FluentResource>>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::new(FALLBACK_LANGUAGE)
+    }
+}
+
+impl Locale {
+    pub fn new(language: &str) -> Self {
+        let bundle = match language {
+            FALLBACK_LANGUAGE => None,
+            _ => load_bundle(language),
+        };
+
+        Locale {
+            language: language.to_string(),
+            bundle,
+            fallback: load_bundle(FALLBACK_LANGUAGE).expect("English is always bundled"),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The text of a message, falling back to English, and then to the id itself
+    pub fn get(&self, id: &str) -> String {
+        self.get_args(id, None)
+    }
+
+    pub fn get_args(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        self.bundle.iter()
+        .chain(std::iter::once(&self.fallback))
+        .find_map(|bundle| format(bundle, id, args))
+        .unwrap_or_else(|| id.to_string())
+    }
+}
+
+fn load_bundle(language: &str) -> Option<FluentBundle<FluentResource>> {
+    let id: LanguageIdentifier = language.parse().ok()?;
+
+    // A mistake in one message shouldn't lose the rest of the file
+    let resource = match FluentResource::try_new(source(language)?.to_string()) {
+        Ok(resource) => resource,
+        Err((resource, errors)) => {
+            eprintln!("Errors in the {} translation: {:?}", language, errors);
+            resource
+        }
+    };
+
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Otherwise arguments get wrapped in Unicode isolation marks, which the font has no glyphs for
+    bundle.set_use_isolating(false);
+
+    if let Err(errors) = bundle.add_resource(resource) {
+        eprintln!("Errors in the {} translation: {:?}", language, errors);
+    }
+
+    Some(bundle)
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = vec![];
+
+    Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+}
+
+/// Switches language whenever the setting changes
+pub fn apply_language(settings: Res<Settings>, mut locale: ResMut<Locale>) {
+    if settings.is_changed() && settings.language != locale.language() {
+        *locale = Locale::new(&settings.language);
+    }
+}
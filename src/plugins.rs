@@ -0,0 +1,269 @@
+use super::*;
+use bevy_egui::EguiPlugin;
+use bevy_kira_audio::AudioPlugin;
+
+/// Labels for systems that others need to be ordered against
+#[derive(SystemLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameSystem {
+    /// Anything that sends `ChooseSquareEvent`s
+    Input,
+    Selecting,
+    /// Escape pausing and resuming
+    Pause,
+    MoveQuadratics,
+    MakeKings,
+}
+
+/// The whole game. Headless apps and tests can add the individual plugins instead.
+pub struct ForetoldPlugin;
+
+impl Plugin for ForetoldPlugin {
+    fn build(&self, app: &mut App) {
+        // Presentation goes first, so picking is set up before the rules look for its events
+        app
+        .insert_resource(Settings::load())
+        .add_plugin(PresentationPlugin)
+        .add_plugin(RulesPlugin)
+        .add_plugin(AiPlugin)
+        .add_plugin(SoundPlugin)
+        .add_plugin(DialoguePlugin)
+        .add_plugin(MenuPlugin)
+        .add_plugin(ControlsPlugin);
+    }
+}
+
+/// The board, the rules and the flow of turns. Needs nothing beyond `MinimalPlugins`.
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        // Picking provides these when the game is rendered; headless, they can be sent by hand
+        if !app.world.contains_resource::<bevy::ecs::event::Events<PickingEvent>>() {
+            app.add_event::<PickingEvent>();
+        }
+
+        app
+        .add_state(GameState::Menu)
+        .insert_resource(SelectedChecker(None))
+        .insert_resource(CheckerMap::default())
+        .insert_resource(PlaceMap::default())
+        .insert_resource(RedMove { start: ivec2(-1, -1), jumped: vec![], jumps: vec![] })
+        .insert_resource(Header(Cow::Borrowed("")))
+        .insert_resource(Table(None))
+        .insert_resource(RedChipStack::default())
+        .insert_resource(BlackChipStack::default())
+        .insert_resource(GameRng::from_env())
+        .insert_resource(GameLog::default())
+        .insert_resource(Variant::default())
+        // Only the full game reads the settings file, so tests play with the defaults
+        .init_resource::<Settings>()
+        .init_resource::<Locale>()
+        .add_event::<ChooseSquareEvent>()
+        .add_event::<TypedMoveEvent>()
+        .add_event::<SelectedCheckerEvent>()
+        .add_event::<EndGameEvent>()
+        .add_event::<QuadLandEvent>()
+        .add_event::<RestartEvent>()
+        .add_startup_system(set_up_board)
+        .add_system(apply_language)
+        // Ahead of input, so a refusal written this frame isn't replaced by the turn's header a frame late
+        .add_system(
+            update_header_with_turn
+            .with_run_criteria(run_if_state_changed.chain(or_if_changed::<Locale>))
+            .before(GameSystem::Input)
+        )
+        .add_system(make_kings.label(GameSystem::MakeKings))
+        .add_system(restart)
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_unpaused)
+            .with_system(advance_timers)
+            .with_system(move_quadratics.label(GameSystem::MoveQuadratics))
+        )
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_playing)
+            .with_system(pick_squares.label(GameSystem::Input))
+            .with_system(play_typed_moves.label(GameSystem::Input))
+            .with_system(selecting.label(GameSystem::Selecting).after(GameSystem::Input))
+            .with_system(enable_valid_spaces.after(GameSystem::Selecting))
+        )
+        .add_system_set(SystemSet::on_enter(GameState::BlackForesight).with_system(check_player_loss))
+        .add_system_set(SystemSet::on_update(GameState::Black).with_system(animate_black_turn))
+        .add_system_set(SystemSet::on_enter(GameState::Red).with_system(play_red_turn))
+        .add_system_set(SystemSet::on_update(GameState::Red).with_system(animate_red_turn))
+        .add_system_set(SystemSet::on_enter(GameState::RedCorrection).with_system(check_player_loss));
+    }
+}
+
+/// Black's moves
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        // The menu starts on Easy
+        .insert_resource(Difficulty::Easy.config())
+        .add_system_set(SystemSet::on_enter(GameState::Black).with_system(enemy_play));
+    }
+}
+
+/// Models, camera, lighting, picking and the on-screen text
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_plugin(EguiPlugin)
+        .add_plugins(DefaultPickingPlugins)
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<HistoryPreview>()
+        .init_resource::<Orbit>()
+        .add_event::<AnimationDoneEvent>()
+        .add_event::<ReachedTargetEvent>()
+        .add_startup_system(set_up_camera)
+        .add_startup_system(set_up_highlights)
+        // After the rules have spawned the board this dresses up
+        .add_startup_system_to_stage(StartupStage::PostStartup, set_up_checkerboard)
+        .add_system_to_stage(CoreStage::PostUpdate, set_up_checker_pickables)
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_unpaused)
+            .with_system(light_flicker)
+            .with_system(lerp_to_targets)
+            .with_system(slerp_to_targets)
+            .with_system(play_animations)
+            .with_system(shatter_ghosts)
+            .with_system(fade_capture_flashes)
+        )
+        .add_system_set(SystemSet::on_enter(GameState::Black).with_system(spawn_foretold_ghost))
+        .add_system_set(SystemSet::on_enter(GameState::RedCorrection).with_system(shatter_foretold_ghost))
+        .add_system_set(SystemSet::on_enter(GameState::BlackForesight).with_system(clear_foretold_ghost))
+        .add_system_set(SystemSet::on_enter(GameState::Outro).with_system(clear_foretold_ghost))
+        .add_system(update_starers)
+        .add_system(show_kings)
+        .add_system(show_last_moves)
+        .add_system(text_header)
+        .add_system(seed_display)
+        .add_system(apply_display_settings)
+        .add_system(screen_text_display)
+        .add_system(
+            highlight_moves
+            .with_run_criteria(run_if_state_changed.chain(or_if_changed::<SelectedChecker>).chain(or_if_places_changed))
+        )
+        .add_system(text_log_display)
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_playing)
+            .with_system(movement)
+            .with_system(orbit_camera)
+            .with_system(debug_win)
+            .with_system(move_history)
+        );
+    }
+}
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        // Dialogue sends typing clicks whether or not there's audio, so either may have registered these first
+        if !app.world.contains_resource::<bevy::ecs::event::Events<SfxEvent>>() {
+            app.add_event::<SfxEvent>();
+        }
+
+        app
+        .add_plugin(AudioPlugin)
+        .init_resource::<MusicPlayer>()
+        .add_startup_system(load_sounds)
+        .add_system(apply_volume)
+        .add_system(play_sfx)
+        .add_system(play_music)
+        .add_system(play_checker_noises.after(GameSystem::MoveQuadratics))
+        .add_system(play_king_noises.before(GameSystem::MakeKings))
+        .add_system(play_end_game_noises)
+        .add_system_set(SystemSet::on_enter(GameState::RedCorrection).with_system(play_invalidated_noise));
+    }
+}
+
+/// The opponent talking through the intro and the endings, and passing comment during play
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<bevy::ecs::event::Events<SfxEvent>>() {
+            app.add_event::<SfxEvent>();
+        }
+
+        app
+        .init_resource::<Input<KeyCode>>()
+        .init_resource::<Input<MouseButton>>()
+        .init_resource::<ScreenText>()
+        .add_asset::<Dialogue>()
+        .init_asset_loader::<DialogueLoader>()
+        .init_resource::<DialogueRunner>()
+        .add_event::<DialogueTrigger>()
+        .init_resource::<Commentary>()
+        .add_event::<RemarkEvent>()
+        .add_startup_system(load_dialogue)
+        .add_system(run_dialogue.with_run_criteria(run_if_unpaused))
+        .add_system(act_on_dialogue)
+        .add_system(advance_dialogue)
+        .add_system(type_screen_text.with_run_criteria(run_if_unpaused))
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_unpaused)
+            .with_system(comment)
+            .with_system(remark_on_moves)
+            .with_system(remark_on_kings.before(GameSystem::MakeKings))
+        )
+        .add_system(remark_on_long_thinks.with_run_criteria(run_if_playing))
+        .add_system_set(SystemSet::on_enter(GameState::BlackForesight).with_system(start_thinking))
+        .add_system_set(
+            SystemSet::on_enter(GameState::RedCorrection)
+            .with_system(remark_on_correction)
+            .with_system(start_thinking)
+        )
+        .add_system_set(SystemSet::on_enter(GameState::Intro).with_system(start_intro))
+        .add_system_set(SystemSet::on_update(GameState::Intro).with_system(escape_intro))
+        .add_system_set(SystemSet::on_exit(GameState::Intro).with_system(begin))
+        .add_system_set(SystemSet::on_enter(GameState::Outro).with_system(end));
+    }
+}
+
+/// The main menu shown before the intro, the pause menu, and the settings screen both lead to
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(ContinueState(None))
+        .insert_resource(MenuPage::Main)
+        .insert_resource(Difficulty::Easy)
+        .add_startup_system(set_up_fonts)
+        .add_system_set(SystemSet::on_update(GameState::Menu).with_system(main_menu))
+        .add_system(pause.label(GameSystem::Pause))
+        .add_system_set(SystemSet::on_update(GameState::Paused).with_system(pause_menu));
+    }
+}
+
+/// Playing with a gamepad or the keyboard instead of the mouse
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BoardCursor>()
+        .init_resource::<MoveEntry>()
+        .add_system(show_board_cursor)
+        .add_system_set(
+            SystemSet::new()
+            .with_run_criteria(run_if_playing)
+            .with_system(gamepad_cursor.label(GameSystem::Input))
+            // Typed moves are only turned into squares a step later, by the rules. Clearing what's been typed takes
+            // Escape before it can pause.
+            .with_system(keyboard_cursor.before(GameSystem::Input).before(GameSystem::Pause))
+            .with_system(move_entry_display)
+        );
+    }
+}
@@ -1,28 +1,155 @@
 use super::*;
+use bevy::ecs::system::EntityCommands;
+use std::f32::consts::TAU;
+
+/// How long a jump takes unless it's told otherwise
+const DEFAULT_JUMP_SECS: f32 = 0.5;
+/// How long a landing takes to flatten and spring back
+const SQUASH_SECS: f32 = 0.2;
+/// How many straight pieces a path is split into to measure its length
+const LENGTH_SAMPLES: usize = 16;
 
 pub struct QuadLandEvent(pub Entity);
 
+/// The scale something goes back to after squashing on landing. Kept from its first squash, so a hop that takes off
+/// mid-squash doesn't leave it flattened for good.
+#[derive(Component)]
+pub struct RestScale(pub Vec3);
+
+/// The shape of a jump between its two ends
+#[derive(Clone, Copy, Debug)]
+pub enum JumpPath {
+    /// A parabola rising `height` world units above the straight line between the ends at its middle. Straight up
+    /// and down is fine too.
+    Arc { height: f32 },
+    /// A cubic Bezier curve, pulled towards two control points
+    Bezier { control_a: Vec3, control_b: Vec3 },
+}
+
 #[derive(Component)]
 pub struct QuadJump {
     start: Vec3,
     end: Vec3,
-    /// Squash/stretch/flip the quadratic
-    factor: f32,
+    path: JumpPath,
     t: f32,
     /// How long in seconds it takes to go from A to B
     time: f32,
+    /// Axis to spin around on the way, and how many turns to make
+    spin: Option<(Vec3, f32)>,
+    /// How much to flatten on landing, as a share of height
+    squash: f32,
+    /// Rotation and scale on takeoff, to spin from, and to squash from if there's no `RestScale` yet
+    takeoff: Option<(Quat, Vec3)>,
 }
 
 impl QuadJump {
-    pub fn new(start: Vec3, end: Vec3, factor: f32, time: f32) -> Self {
+    pub fn arc(start: Vec3, end: Vec3, height: f32) -> Self {
+        QuadJump::along(start, end, JumpPath::Arc { height })
+    }
+
+    pub fn bezier(start: Vec3, control_a: Vec3, control_b: Vec3, end: Vec3) -> Self {
+        QuadJump::along(start, end, JumpPath::Bezier { control_a, control_b })
+    }
+
+    pub fn along(start: Vec3, end: Vec3, path: JumpPath) -> Self {
         QuadJump {
             start,
             end,
-            factor,
+            path,
             t: 0.0,
-            time
+            time: DEFAULT_JUMP_SECS,
+            spin: None,
+            squash: 0.0,
+            takeoff: None,
+        }
+    }
+
+    /// Takes `secs` to land
+    pub fn over(mut self, secs: f32) -> Self {
+        self.time = secs;
+        self
+    }
+
+    /// Takes however long it needs to cover the path at `speed` world units a second
+    pub fn at_speed(mut self, speed: f32) -> Self {
+        if speed > 0.0 {
+            self.time = self.length() / speed;
+        }
+        self
+    }
+
+    /// Makes `turns` full turns around `axis` on the way
+    pub fn spin(mut self, axis: Vec3, turns: f32) -> Self {
+        self.spin = (axis != Vec3::ZERO).then(|| (axis.normalize(), turns));
+        self
+    }
+
+    /// Flattens by `amount` of its height on landing, then springs back
+    pub fn squash(mut self, amount: f32) -> Self {
+        self.squash = amount;
+        self
+    }
+
+    /// Where the jump is `t` of the way through, from 0 to 1
+    pub fn position(&self, t: f32) -> Vec3 {
+        match self.path {
+            JumpPath::Arc { height } => self.start.lerp(self.end, t) + Vec3::Y * 4.0 * height * t * (1.0 - t),
+            JumpPath::Bezier { control_a, control_b } => {
+                let u = 1.0 - t;
+                self.start * u.powi(3) + control_a * 3.0 * u * u * t + control_b * 3.0 * u * t * t + self.end * t.powi(3)
+            },
         }
     }
+
+    /// Roughly how far the jump travels along its path
+    pub fn length(&self) -> f32 {
+        (1..=LENGTH_SAMPLES)
+        .map(|i| {
+            let (a, b) = ((i - 1) as f32 / LENGTH_SAMPLES as f32, i as f32 / LENGTH_SAMPLES as f32);
+            self.position(a).distance(self.position(b))
+        })
+        .sum()
+    }
+
+    /// Moves the jump on by `dt` seconds. Returns true once it's landed.
+    fn advance(&mut self, dt: f32, tf: &mut Transform) -> bool {
+        let (rotation, _) = *self.takeoff.get_or_insert((tf.rotation, tf.scale));
+
+        self.t = match self.time > 0.0 {
+            true => (self.t + dt / self.time).min(1.0),
+            false => 1.0,
+        };
+
+        tf.translation = self.position(self.t);
+
+        if let Some((axis, turns)) = self.spin {
+            tf.rotation = Quat::from_axis_angle(axis, turns * TAU * self.t) * rotation;
+        }
+
+        self.t >= 1.0
+    }
+
+    /// Squashes whatever landed, if the jump asks for it
+    fn land(&self, entity: &mut EntityCommands, rest: Option<&RestScale>) {
+        if self.squash <= 0.0 {
+            return;
+        }
+
+        let scale = match (rest, self.takeoff) {
+            (Some(rest), _) => rest.0,
+            (None, Some((_, scale))) => scale,
+            (None, None) => return,
+        };
+
+        let squashed = scale * vec3(1.0 + self.squash / 2.0, 1.0 - self.squash, 1.0 + self.squash / 2.0);
+
+        entity
+        .insert(RestScale(scale))
+        .insert(Animation::new(Track::sequence([
+            Track::scale(squashed, SQUASH_SECS * 0.3, Ease::QuadOut),
+            Track::scale(scale, SQUASH_SECS * 0.7, Ease::BackOut),
+        ])));
+    }
 }
 
 #[derive(Component)]
@@ -36,86 +163,95 @@ impl MultiQuadJump {
             jumps
         }
     }
+
+    /// Whether every jump has landed
+    pub fn is_finished(&self) -> bool {
+        self.jumps.is_empty()
+    }
 }
 
 pub fn move_quadratics(
     mut commands: Commands,
-    mut q: Query<(Entity, &mut Transform, &mut QuadJump)>,
-    mut multis: Query<(Entity, &mut Transform, &mut MultiQuadJump), Without<QuadJump>>,
+    mut q: Query<(Entity, &mut Transform, &mut QuadJump, Option<&RestScale>)>,
+    mut multis: Query<(Entity, &mut Transform, &mut MultiQuadJump, Option<&RestScale>), Without<QuadJump>>,
     time: Res<Time>,
     mut events: EventWriter<QuadLandEvent>,
 ) {
-    for (id, mut tf, mut quad) in q.iter_mut() {
-        let dt = time.delta_seconds();
-
-        let (x, y, start_to_end);
-        {
-            y = quad.end.y - quad.start.y;
-            let height_adjusted_end = quad.end - vec3(0.0, y, 0.0);
-            x = quad.start.distance(height_adjusted_end);
-            start_to_end = (height_adjusted_end - quad.start).normalize();
+    let dt = time.delta_seconds();
+
+    for (id, mut tf, mut quad, rest) in q.iter_mut() {
+        if quad.advance(dt, &mut tf) {
+            let mut entity = commands.entity(id);
+            entity.remove::<QuadJump>();
+            quad.land(&mut entity, rest);
+
+            events.send(QuadLandEvent(id));
         }
+    }
 
-        let a = -1.0 * quad.factor;
+    for (id, mut tf, mut multi, rest) in multis.iter_mut() {
+        let landed = match multi.jumps.first_mut() {
+            Some(quad) => quad.advance(dt, &mut tf),
+            None => {
+                commands.entity(id).remove::<MultiQuadJump>();
+                continue;
+            },
+        };
 
-        let b = (y - (a * x.powi(2))) / x;
+        if landed {
+            let quad = multi.jumps.remove(0);
+
+            // Only the last landing squashes, so hops chain straight into each other
+            if multi.is_finished() {
+                quad.land(&mut commands.entity(id), rest);
+            }
 
-        let mut t = quad.t + (dt / quad.time);
-        if t > 1.0 {
-            t = 1.0;
-            commands.entity(id).remove::<QuadJump>();
             events.send(QuadLandEvent(id));
         }
-        quad.t = t;
-
-        let (out_x, out_y) = (
-            x * t,
-            a * (x * t).powi(2) + b * x * t
-        );
-
-        let mut out_pos = quad.start + (start_to_end * out_x);
-        out_pos.y += out_y;
-
-        tf.translation = out_pos;
-    }
-
-    for (id, mut tf, mut multi) in multis.iter_mut() {
-        if let Some(quad) = multi.jumps.first_mut() {
-            let start = quad.start;
-    
-            let dt = time.delta_seconds();
-    
-            let (x, y, start_to_end);
-            {
-                y = quad.end.y - start.y;
-                let height_adjusted_end = quad.end - vec3(0.0, y, 0.0);
-                x = start.distance(height_adjusted_end);
-                start_to_end = (height_adjusted_end - start).normalize();
-            }
-    
-            let a = -1.0 * quad.factor;
-    
-            let b = (y - (a * x.powi(2))) / x;
-    
-            let mut t = quad.t + (dt / quad.time);
-            quad.t = t;
-            if t > 1.0 {
-                t = 1.0;
-                multi.jumps.remove(0);
-                events.send(QuadLandEvent(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_drop_stays_finite_and_lands_on_its_end() {
+        let (start, end) = (vec3(0.2, 1.0, -0.1), vec3(0.2, 0.0, -0.1));
+
+        for mut jump in [QuadJump::arc(start, end, 0.0), QuadJump::arc(start, end, 0.3), QuadJump::arc(end, end, 0.3)] {
+            let mut tf = Transform::from_translation(jump.start);
+            let mut landed = false;
+
+            for _ in 0..100 {
+                landed = jump.advance(0.01, &mut tf);
+                assert!(tf.translation.is_finite(), "{:?}", tf.translation);
+                if landed {
+                    break;
+                }
             }
-    
-            let (out_x, out_y) = (
-                x * t,
-                a * (x * t).powi(2) + b * x * t
-            );
-    
-            let mut out_pos = start + (start_to_end * out_x);
-            out_pos.y += out_y;
-
-            tf.translation = out_pos;
-        } else {
-            commands.entity(id).remove::<MultiQuadJump>();
+
+            assert!(landed);
+            assert_eq!(tf.translation, jump.end);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn zero_length_jump_lands_in_one_step() {
+        let mut jump = QuadJump::arc(Vec3::ZERO, Vec3::X, 0.1).over(0.0);
+        let mut tf = Transform::default();
+
+        assert!(jump.advance(0.0, &mut tf));
+        assert_eq!(tf.translation, Vec3::X);
+    }
+
+    #[test]
+    fn at_speed_takes_its_length_over_the_speed() {
+        let jump = QuadJump::bezier(Vec3::ZERO, vec3(0.0, 1.0, 0.0), vec3(1.0, 1.0, 0.0), Vec3::X).at_speed(2.0);
+        assert!((jump.time - jump.length() / 2.0).abs() < 1e-5);
+
+        // A speed of zero or less leaves the time alone rather than dividing by it
+        let jump = QuadJump::arc(Vec3::ZERO, Vec3::X, 0.1).at_speed(0.0);
+        assert_eq!(jump.time, DEFAULT_JUMP_SECS);
+    }
+}
@@ -0,0 +1,127 @@
+use rand::prelude::*;
+
+/// The only source of randomness in the game, so any game can be replayed exactly from its seed.
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Uses the seed passed as `--seed <n>` or in the `FORETOLD_SEED` environment variable, or a random one otherwise
+    pub fn from_env() -> Self {
+        let mut args = std::env::args().skip_while(|a| a != "--seed").skip(1);
+
+        let seed = args.next()
+        .or_else(|| std::env::var("FORETOLD_SEED").ok())
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| rand::thread_rng().gen());
+
+        GameRng::new(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// A separate sequence derived from the seed, for randomness that shouldn't change how the game plays.
+    /// Each `stream` gets its own.
+    pub fn substream(&self, stream: u64) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    /// Switches to a fresh seed for the next game. It's drawn from the current sequence,
+    /// so a whole session still replays from the first seed, and each game replays from its own.
+    pub fn next_game(&mut self) {
+        *self = GameRng::new(self.rng.gen());
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// One of `GameRng`'s substreams, started over whenever a new game changes the seed
+#[derive(Default)]
+pub struct SubRng(Option<(u64, StdRng)>);
+
+impl SubRng {
+    pub fn get(&mut self, game: &GameRng, stream: u64) -> &mut StdRng {
+        let stale = self.0.as_ref().is_none_or(|(seed, _)| *seed != game.seed());
+        if stale {
+            self.0 = Some((game.seed(), game.substream(stream)));
+        }
+
+        &mut self.0.as_mut().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws(rng: &mut impl Rng) -> Vec<u64> {
+        (0..8).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_draws() {
+        assert_eq!(draws(&mut GameRng::new(42)), draws(&mut GameRng::new(42)));
+        assert_ne!(draws(&mut GameRng::new(42)), draws(&mut GameRng::new(43)));
+    }
+
+    #[test]
+    fn next_game_follows_from_the_seed() {
+        let (mut a, mut b) = (GameRng::new(7), GameRng::new(7));
+        a.next_game();
+        b.next_game();
+
+        assert_eq!(a.seed(), b.seed());
+        assert_ne!(a.seed(), 7);
+        assert_eq!(draws(&mut a), draws(&mut b));
+    }
+
+    #[test]
+    fn substreams_are_left_alone_by_main_draws() {
+        let mut game = GameRng::new(3);
+        let before = draws(&mut game.substream(1));
+
+        draws(&mut game);
+
+        assert_eq!(draws(&mut game.substream(1)), before);
+        assert_ne!(draws(&mut game.substream(2)), before);
+        assert_ne!(draws(&mut GameRng::new(3)), before);
+    }
+
+    #[test]
+    fn sub_rng_starts_over_with_a_new_seed() {
+        let mut game = GameRng::new(5);
+        let mut sub = SubRng::default();
+
+        let first = sub.get(&game, 1).gen::<u64>();
+        assert_ne!(sub.get(&game, 1).gen::<u64>(), first);
+
+        game.next_game();
+        assert_eq!(sub.get(&game, 1).gen::<u64>(), game.substream(1).gen::<u64>());
+    }
+}
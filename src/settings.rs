@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use bevy_egui::{egui, EguiContext};
+use std::ops::RangeInclusive;
+
+use crate::locale::{Locale, LANGUAGES};
+
+const SETTINGS_PATH: &str = "foretold.cfg";
+
+/// Ranges each slider allows. Values read from the file are kept inside them too, since a speed of 0 would stop the
+/// game in its tracks.
+const VOLUME_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+const SPEED_RANGE: RangeInclusive<f32> = 0.25..=4.0;
+const TYPE_SPEED_RANGE: RangeInclusive<f32> = 10.0..=200.0;
+
+/// Reads a number, clamped to `range`. Anything that isn't a number, like NaN, is refused.
+fn parse_in(value: &str, range: RangeInclusive<f32>) -> Option<f32> {
+    let v: f32 = value.parse().ok()?;
+    (!v.is_nan()).then(|| v.clamp(*range.start(), *range.end()))
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+
+    pub fn message_id(self) -> &'static str {
+        match self {
+            Theme::Dark => "theme-dark",
+            Theme::Light => "theme-light",
+        }
+    }
+}
+
+/// Player preferences, kept in `foretold.cfg` between sessions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// Scales every other volume
+    pub volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    /// Multiplies how quickly the camera moves
+    pub camera_sensitivity: f32,
+    /// Lets the camera be dragged around the board and zoomed, as well as leaned with W/S
+    pub orbit_camera: bool,
+    /// Multiplies how quickly checkers hop about
+    pub animation_speed: f32,
+    pub skip_intro: bool,
+    /// Multiplies how quickly the opponent talks
+    pub text_speed: f32,
+    /// Characters a second that lines are typed out at
+    pub type_speed: f32,
+    pub typing_sounds: bool,
+    pub theme: Theme,
+    pub fullscreen: bool,
+    /// Code of the language to show text in, such as `en`
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 0.6,
+            camera_sensitivity: 1.0,
+            orbit_camera: false,
+            animation_speed: 1.0,
+            skip_intro: false,
+            text_speed: 1.0,
+            type_speed: 40.0,
+            typing_sounds: true,
+            theme: Theme::Dark,
+            fullscreen: false,
+            language: "en".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings file, falling back to the defaults for anything missing or unreadable
+    pub fn load() -> Self {
+        match std::fs::read_to_string(SETTINGS_PATH) {
+            Ok(text) => Settings::parse(&text),
+            Err(_) => Settings::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = std::fs::write(SETTINGS_PATH, self.to_text()) {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+
+    /// Parses one `key = value` per line. Bad lines are reported and skipped rather than losing every other setting.
+    pub fn parse(text: &str) -> Self {
+        let mut settings = Settings::default();
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    eprintln!("Expected key = value in settings, found `{}`", line);
+                    continue;
+                }
+            };
+
+            let parsed = match key {
+                "volume" => parse_in(value, VOLUME_RANGE).map(|v| settings.volume = v).is_some(),
+                "sfx_volume" => parse_in(value, VOLUME_RANGE).map(|v| settings.sfx_volume = v).is_some(),
+                "music_volume" => parse_in(value, VOLUME_RANGE).map(|v| settings.music_volume = v).is_some(),
+                "camera_sensitivity" => parse_in(value, SPEED_RANGE).map(|v| settings.camera_sensitivity = v).is_some(),
+                "orbit_camera" => value.parse().map(|v| settings.orbit_camera = v).is_ok(),
+                "animation_speed" => parse_in(value, SPEED_RANGE).map(|v| settings.animation_speed = v).is_some(),
+                "skip_intro" => value.parse().map(|v| settings.skip_intro = v).is_ok(),
+                "text_speed" => parse_in(value, SPEED_RANGE).map(|v| settings.text_speed = v).is_some(),
+                "type_speed" => parse_in(value, TYPE_SPEED_RANGE).map(|v| settings.type_speed = v).is_some(),
+                "typing_sounds" => value.parse().map(|v| settings.typing_sounds = v).is_ok(),
+                "theme" => match Theme::ALL.into_iter().find(|t| t.name().eq_ignore_ascii_case(value)) {
+                    Some(theme) => {
+                        settings.theme = theme;
+                        true
+                    },
+                    None => false,
+                },
+                "fullscreen" => value.parse().map(|v| settings.fullscreen = v).is_ok(),
+                "language" => match LANGUAGES.iter().find(|(code, _)| *code == value) {
+                    Some((code, _)) => {
+                        settings.language = code.to_string();
+                        true
+                    },
+                    None => false,
+                },
+                _ => {
+                    eprintln!("Unknown setting `{}`", key);
+                    continue;
+                }
+            };
+
+            if !parsed {
+                eprintln!("Invalid value for `{}`: `{}`", key, value);
+            }
+        }
+
+        settings
+    }
+
+    pub fn to_text(&self) -> String {
+        format!(
+            "volume = {}\nsfx_volume = {}\nmusic_volume = {}\ncamera_sensitivity = {}\norbit_camera = {}\nanimation_speed = {}\nskip_intro = {}\ntext_speed = {}\ntype_speed = {}\ntyping_sounds = {}\ntheme = {}\nfullscreen = {}\nlanguage = {}\n",
+            self.volume,
+            self.sfx_volume,
+            self.music_volume,
+            self.camera_sensitivity,
+            self.orbit_camera,
+            self.animation_speed,
+            self.skip_intro,
+            self.text_speed,
+            self.type_speed,
+            self.typing_sounds,
+            self.theme.name(),
+            self.fullscreen,
+            self.language,
+        )
+    }
+
+    /// How long an animation that normally takes `secs` should take
+    pub fn animation_time(&self, secs: f32) -> f32 {
+        secs / self.animation_speed
+    }
+
+    /// How fast something that normally moves at `speed` should move
+    pub fn animation_rate(&self, speed: f32) -> f32 {
+        speed * self.animation_speed
+    }
+}
+
+/// Controls for every setting. Returns true once the player is done with them.
+pub fn settings_ui(ui: &mut egui::Ui, settings: &mut Settings, locale: &Locale) -> bool {
+    ui.add(egui::Slider::new(&mut settings.volume, VOLUME_RANGE).text(locale.get("settings-volume")));
+    ui.add(egui::Slider::new(&mut settings.sfx_volume, VOLUME_RANGE).text(locale.get("settings-sfx-volume")));
+    ui.add(egui::Slider::new(&mut settings.music_volume, VOLUME_RANGE).text(locale.get("settings-music-volume")));
+    ui.add(egui::Slider::new(&mut settings.camera_sensitivity, SPEED_RANGE).text(locale.get("settings-camera-sensitivity")));
+    ui.checkbox(&mut settings.orbit_camera, locale.get("settings-orbit-camera"));
+    ui.add(egui::Slider::new(&mut settings.animation_speed, SPEED_RANGE).text(locale.get("settings-animation-speed")));
+    ui.add(egui::Slider::new(&mut settings.text_speed, SPEED_RANGE).text(locale.get("settings-text-speed")));
+    ui.add(egui::Slider::new(&mut settings.type_speed, TYPE_SPEED_RANGE).text(locale.get("settings-typing-speed")));
+    ui.checkbox(&mut settings.typing_sounds, locale.get("settings-typing-sounds"));
+    ui.checkbox(&mut settings.skip_intro, locale.get("settings-skip-intro"));
+
+    egui::ComboBox::from_label(locale.get("settings-theme"))
+    .selected_text(locale.get(settings.theme.message_id()))
+    .show_ui(ui, |ui| {
+        for theme in Theme::ALL {
+            ui.selectable_value(&mut settings.theme, theme, locale.get(theme.message_id()));
+        }
+    });
+
+    ui.checkbox(&mut settings.fullscreen, locale.get("settings-fullscreen"));
+
+    // Each language is listed under its own name, so it can be found without reading the current one
+    let current = LANGUAGES.iter().find(|(code, _)| *code == settings.language).map_or("", |(_, name)| *name);
+    egui::ComboBox::from_label(locale.get("settings-language"))
+    .selected_text(current)
+    .show_ui(ui, |ui| {
+        for (code, name) in LANGUAGES {
+            ui.selectable_value(&mut settings.language, code.to_string(), name);
+        }
+    });
+
+    ui.add_space(16.);
+    ui.button(locale.get("settings-back")).clicked()
+}
+
+pub fn apply_display_settings(
+    settings: Res<Settings>,
+    mut ctx: ResMut<EguiContext>,
+    mut windows: ResMut<Windows>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    ctx.ctx_mut().set_visuals(match settings.theme {
+        Theme::Dark => egui::Visuals::dark(),
+        Theme::Light => egui::Visuals::light(),
+    });
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_mode(match settings.fullscreen {
+            true => WindowMode::BorderlessFullscreen,
+            false => WindowMode::Windowed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_text() {
+        let settings = Settings {
+            volume: 0.5,
+            camera_sensitivity: 2.5,
+            orbit_camera: true,
+            skip_intro: true,
+            type_speed: 120.0,
+            theme: Theme::Light,
+            fullscreen: true,
+            language: LANGUAGES.last().unwrap().0.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(Settings::parse(&settings.to_text()), settings);
+        assert_eq!(Settings::parse(&Settings::default().to_text()), Settings::default());
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let settings = Settings::parse("volume = 3\ntext_speed = 0\nanimation_speed = -1\ntype_speed = inf");
+
+        assert_eq!(settings.volume, *VOLUME_RANGE.end());
+        assert_eq!(settings.text_speed, *SPEED_RANGE.start());
+        assert_eq!(settings.animation_speed, *SPEED_RANGE.start());
+        assert_eq!(settings.type_speed, *TYPE_SPEED_RANGE.end());
+    }
+
+    #[test]
+    fn bad_lines_keep_the_default_and_spare_the_rest() {
+        let text = "volume = NaN\nmystery = 4\nanimation_speed 2\ntheme = Purple\nsfx_volume = loud\n# volume = 0\nmusic_volume = 0.25";
+        let settings = Settings::parse(text);
+
+        assert_eq!(settings, Settings { music_volume: 0.25, ..Default::default() });
+    }
+}
@@ -1,47 +1,361 @@
-use bevy::{prelude::*, ecs::system::EntityCommands};
+use bevy::{prelude::*, ecs::system::{Command, EntityCommands}};
 
-#[derive(Component)]
+/// The shortest gap a repeating timer can have, so a long frame can't make it run without end
+const MIN_REPEAT_SECS: f32 = 0.01;
+
+/// An action to run after a delay, or over and over
 pub struct TimerAction {
     secs_left: f32,
+    /// How long to wait between runs, for timers that repeat
+    repeat: Option<f32>,
     paused: bool,
-    action: Option<Box<dyn FnOnce(Entity, &mut Commands) + Send + Sync>>
+    /// Lets the timer be cancelled or paused later
+    name: Option<&'static str>,
+    action: Box<dyn FnMut(&mut Commands) + Send + Sync>,
 }
 
 impl TimerAction {
-    pub fn new<F: 'static + FnOnce(Entity, &mut Commands) + Send + Sync>(time: f32, action: F) -> TimerAction {
+    pub fn new<F: 'static + FnOnce(&mut Commands) + Send + Sync>(time: f32, action: F) -> TimerAction {
+        let mut action = Some(action);
+
         TimerAction {
             secs_left: time,
+            repeat: None,
             paused: false,
-            action: Some(Box::new(action))
+            name: None,
+            action: Box::new(move |commands| {
+                if let Some(action) = action.take() {
+                    action(commands);
+                }
+            }),
         }
     }
+
+    /// Runs `action` every `every` seconds until it's cancelled. Gaps shorter than a hundredth of a second are
+    /// lengthened to that.
+    pub fn repeating<F: 'static + FnMut(&mut Commands) + Send + Sync>(every: f32, action: F) -> TimerAction {
+        let every = every.max(MIN_REPEAT_SECS);
+
+        TimerAction {
+            secs_left: every,
+            repeat: Some(every),
+            paused: false,
+            name: None,
+            action: Box::new(action),
+        }
+    }
+
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Doesn't start counting down until it's resumed
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
 }
 
-pub fn advance_timers(mut query: Query<(Entity, &mut TimerAction)>, time: Res<Time>, mut commands: Commands) {
-    for (e, mut timer) in query.iter_mut() {
-        if !timer.paused {
-            timer.secs_left -= time.delta_seconds();
-            
-            if timer.secs_left <= 0.0 {
-                if let Some(act) = timer.action.take() {
-                    act(e, &mut commands);
+/// Every timer on an entity. As a resource, holds the timers that aren't tied to any entity.
+#[derive(Component, Default)]
+pub struct TimerQueue(Vec<TimerAction>);
+
+impl TimerQueue {
+    fn advance(&mut self, dt: f32, commands: &mut Commands) {
+        for timer in self.0.iter_mut().filter(|t| !t.paused) {
+            timer.secs_left -= dt;
+
+            // A repeating timer catches up on every run it missed during a long frame
+            while timer.secs_left <= 0.0 {
+                (timer.action)(commands);
+
+                match timer.repeat {
+                    Some(every) => timer.secs_left += every,
+                    None => break,
                 }
-                
-                commands.entity(e).remove::<TimerAction>();
             }
         }
+
+        self.0.retain(|t| t.repeat.is_some() || t.secs_left > 0.0);
+    }
+
+    fn named(&mut self, name: &'static str) -> impl Iterator<Item = &mut TimerAction> {
+        self.0.iter_mut().filter(move |t| t.name == Some(name))
+    }
+}
+
+pub fn advance_timers(
+    mut commands: Commands,
+    mut queues: Query<&mut TimerQueue>,
+    global: Option<ResMut<TimerQueue>>,
+    time: Res<Time>,
+) {
+    for mut queue in queues.iter_mut() {
+        queue.advance(time.delta_seconds(), &mut commands);
+    }
+
+    if let Some(mut global) = global {
+        global.advance(time.delta_seconds(), &mut commands);
+    }
+}
+
+/// Changes to the timers on an entity, or to the entity-less ones if there's no entity
+enum TimerCommand {
+    Add(Option<Entity>, TimerAction),
+    Cancel(Option<Entity>, &'static str),
+    SetPaused(Option<Entity>, &'static str, bool),
+}
+
+impl TimerCommand {
+    fn entity(&self) -> Option<Entity> {
+        match self {
+            TimerCommand::Add(e, _) | TimerCommand::Cancel(e, _) | TimerCommand::SetPaused(e, _, _) => *e,
+        }
+    }
+}
+
+impl Command for TimerCommand {
+    fn write(self, world: &mut World) {
+        // Only adding a timer needs a queue to be made. Cancelling or pausing where there's none does nothing.
+        let adding = matches!(self, TimerCommand::Add(..));
+
+        let mut queue = match self.entity() {
+            Some(e) => {
+                // The entity may have gone since the command was queued, taking its timers with it
+                match world.get_entity_mut(e) {
+                    Some(mut entity) if adding && !entity.contains::<TimerQueue>() => { entity.insert(TimerQueue::default()); },
+                    Some(_) => {},
+                    None => return,
+                }
+                match world.get_mut::<TimerQueue>(e) {
+                    Some(queue) => queue,
+                    None => return,
+                }
+            },
+            None if adding => world.get_resource_or_insert_with(TimerQueue::default),
+            None => match world.get_resource_mut::<TimerQueue>() {
+                Some(queue) => queue,
+                None => return,
+            },
+        };
+
+        match self {
+            TimerCommand::Add(_, timer) => queue.0.push(timer),
+            TimerCommand::Cancel(_, name) => queue.0.retain(|t| t.name != Some(name)),
+            TimerCommand::SetPaused(_, name, paused) => queue.named(name).for_each(|t| t.paused = paused),
+        }
     }
 }
 
 /// Helper trait to allow easily delaying actions
 pub trait CommandWithDelay where Self: Sized {
+    /// Runs `actions` on the entity after `delay` seconds. Any other timers on the entity carry on as they were.
     fn with_delay<F: 'static + FnOnce(&mut EntityCommands) + Sync + Send>(&mut self, delay: f32, actions: F) -> &mut Self;
+    /// Like `with_delay`, but can be cancelled or paused by name
+    fn with_named_delay<F: 'static + FnOnce(&mut EntityCommands) + Sync + Send>(&mut self, name: &'static str, delay: f32, actions: F) -> &mut Self;
+    /// Runs `actions` on the entity every `every` seconds until it's cancelled by name
+    fn with_repeating<F: 'static + FnMut(&mut EntityCommands) + Sync + Send>(&mut self, name: &'static str, every: f32, actions: F) -> &mut Self;
+    fn cancel_timer(&mut self, name: &'static str) -> &mut Self;
+    fn pause_timer(&mut self, name: &'static str) -> &mut Self;
+    fn resume_timer(&mut self, name: &'static str) -> &mut Self;
 }
 
 impl<'w, 's, 'a> CommandWithDelay for EntityCommands<'w, 's, 'a> {
     fn with_delay<F: 'static + FnOnce(&mut EntityCommands) + Sync + Send>(&mut self, delay: f32, actions: F) -> &mut Self {
-        self.insert(TimerAction::new(delay, Box::new(|e, commands: &mut Commands| {
+        let e = self.id();
+        self.commands().add(TimerCommand::Add(Some(e), TimerAction::new(delay, move |commands| {
             actions(&mut commands.entity(e));
-        })))
+        })));
+        self
     }
-}
\ No newline at end of file
+
+    fn with_named_delay<F: 'static + FnOnce(&mut EntityCommands) + Sync + Send>(&mut self, name: &'static str, delay: f32, actions: F) -> &mut Self {
+        let e = self.id();
+        self.commands().add(TimerCommand::Add(Some(e), TimerAction::new(delay, move |commands| {
+            actions(&mut commands.entity(e));
+        }).named(name)));
+        self
+    }
+
+    fn with_repeating<F: 'static + FnMut(&mut EntityCommands) + Sync + Send>(&mut self, name: &'static str, every: f32, mut actions: F) -> &mut Self {
+        let e = self.id();
+        self.commands().add(TimerCommand::Add(Some(e), TimerAction::repeating(every, move |commands| {
+            actions(&mut commands.entity(e));
+        }).named(name)));
+        self
+    }
+
+    fn cancel_timer(&mut self, name: &'static str) -> &mut Self {
+        let e = self.id();
+        self.commands().add(TimerCommand::Cancel(Some(e), name));
+        self
+    }
+
+    fn pause_timer(&mut self, name: &'static str) -> &mut Self {
+        let e = self.id();
+        self.commands().add(TimerCommand::SetPaused(Some(e), name, true));
+        self
+    }
+
+    fn resume_timer(&mut self, name: &'static str) -> &mut Self {
+        let e = self.id();
+        self.commands().add(TimerCommand::SetPaused(Some(e), name, false));
+        self
+    }
+}
+
+/// Timers that aren't tied to any entity, so they outlive whatever set them going
+pub trait GlobalTimers {
+    fn add_timer(&mut self, timer: TimerAction);
+    fn cancel_timer(&mut self, name: &'static str);
+    fn pause_timer(&mut self, name: &'static str);
+    fn resume_timer(&mut self, name: &'static str);
+}
+
+impl<'w, 's> GlobalTimers for Commands<'w, 's> {
+    fn add_timer(&mut self, timer: TimerAction) {
+        self.add(TimerCommand::Add(None, timer));
+    }
+
+    fn cancel_timer(&mut self, name: &'static str) {
+        self.add(TimerCommand::Cancel(None, name));
+    }
+
+    fn pause_timer(&mut self, name: &'static str) {
+        self.add(TimerCommand::SetPaused(None, name, true));
+    }
+
+    fn resume_timer(&mut self, name: &'static str) {
+        self.add(TimerCommand::SetPaused(None, name, false));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+    use std::sync::{Arc, atomic::{AtomicU32, Ordering}};
+
+    #[derive(Component)]
+    struct First;
+
+    #[derive(Component)]
+    struct Second;
+
+    struct Done;
+
+    struct Dt(f32);
+
+    /// `advance_timers`, but with a chosen frame length since `Time` can't be stepped by hand
+    fn advance_by(mut commands: Commands, mut queues: Query<&mut TimerQueue>, global: Option<ResMut<TimerQueue>>, dt: Res<Dt>) {
+        for mut queue in queues.iter_mut() {
+            queue.advance(dt.0, &mut commands);
+        }
+
+        if let Some(mut global) = global {
+            global.advance(dt.0, &mut commands);
+        }
+    }
+
+    fn apply(world: &mut World, f: impl FnOnce(&mut Commands)) {
+        let mut queue = CommandQueue::default();
+        f(&mut Commands::new(&mut queue, world));
+        queue.apply(world);
+    }
+
+    fn tick(world: &mut World, dt: f32) {
+        world.insert_resource(Dt(dt));
+        SystemStage::single_threaded().with_system(advance_by).run(world);
+    }
+
+    fn counter() -> (Arc<AtomicU32>, impl FnMut(&mut EntityCommands) + Send + Sync) {
+        let count = Arc::new(AtomicU32::new(0));
+        let counted = count.clone();
+        (count, move |_: &mut EntityCommands| { counted.fetch_add(1, Ordering::Relaxed); })
+    }
+
+    #[test]
+    fn two_delays_on_one_entity_both_fire() {
+        let mut world = World::new();
+        let e = world.spawn().id();
+
+        apply(&mut world, |commands| {
+            commands.entity(e)
+                .with_delay(0.25, |e| { e.insert(First); })
+                .with_delay(0.5, |e| { e.insert(Second); });
+        });
+
+        tick(&mut world, 0.375);
+        assert!(world.get::<First>(e).is_some());
+        assert!(world.get::<Second>(e).is_none());
+
+        tick(&mut world, 0.25);
+        assert!(world.get::<Second>(e).is_some());
+    }
+
+    #[test]
+    fn named_timers_pause_resume_and_cancel() {
+        let mut world = World::new();
+        let e = world.spawn().id();
+        let (count, tally) = counter();
+
+        apply(&mut world, |commands| {
+            commands.entity(e)
+                .with_named_delay("land", 0.25, |e| { e.insert(First); })
+                .with_repeating("tick", 0.25, tally)
+                .pause_timer("land");
+        });
+
+        tick(&mut world, 0.5);
+        assert!(world.get::<First>(e).is_none());
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+
+        apply(&mut world, |commands| { commands.entity(e).resume_timer("land").cancel_timer("tick"); });
+        tick(&mut world, 0.5);
+        assert!(world.get::<First>(e).is_some());
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn repeating_timers_catch_up_after_a_long_frame() {
+        let mut world = World::new();
+        let e = world.spawn().id();
+        let (count, tally) = counter();
+
+        apply(&mut world, |commands| { commands.entity(e).with_repeating("tick", 0.25, tally); });
+        tick(&mut world, 1.0);
+        assert_eq!(count.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn repeating_timers_keep_a_minimum_gap() {
+        let mut world = World::new();
+        let e = world.spawn().id();
+        let (count, tally) = counter();
+
+        apply(&mut world, |commands| { commands.entity(e).with_repeating("tick", 0.0, tally); });
+        tick(&mut world, 1.0);
+
+        let runs = count.load(Ordering::Relaxed);
+        assert!((99..=101).contains(&runs), "{} runs", runs);
+    }
+
+    #[test]
+    fn timers_without_an_entity_fire() {
+        let mut world = World::new();
+
+        apply(&mut world, |commands| {
+            commands.add_timer(TimerAction::new(0.25, |commands| commands.insert_resource(Done)));
+            commands.add_timer(TimerAction::new(0.25, |commands| commands.insert_resource(Dt(-1.0))).named("never"));
+            commands.cancel_timer("never");
+        });
+
+        tick(&mut world, 0.125);
+        assert!(world.get_resource::<Done>().is_none());
+
+        tick(&mut world, 0.25);
+        assert!(world.get_resource::<Done>().is_some());
+        assert_eq!(world.get_resource::<Dt>().unwrap().0, 0.25);
+    }
+}
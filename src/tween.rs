@@ -0,0 +1,274 @@
+use bevy::{prelude::*, ecs::system::EntityCommands};
+use std::collections::VecDeque;
+
+/// How a tween speeds up and slows down between its start and end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ease {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+    /// Overshoots a little, then settles back
+    BackOut,
+    BounceOut,
+}
+
+impl Ease {
+    /// Maps how far through a tween is, from 0 to 1, to how far along its value should be
+    pub fn apply(self, t: f32) -> f32 {
+        use std::f32::consts::PI;
+
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Ease::QuadInOut => if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 },
+            Ease::CubicIn => t.powi(3),
+            Ease::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Ease::CubicInOut => if t < 0.5 { 4.0 * t.powi(3) } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 },
+            Ease::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Ease::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            },
+            Ease::BounceOut => {
+                let (n1, d1) = (7.5625, 2.75);
+                if t < 1.0 / d1 {
+                    n1 * t * t
+                } else if t < 2.0 / d1 {
+                    let t = t - 1.5 / d1;
+                    n1 * t * t + 0.75
+                } else if t < 2.5 / d1 {
+                    let t = t - 2.25 / d1;
+                    n1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d1;
+                    n1 * t * t + 0.984375
+                }
+            },
+        }
+    }
+}
+
+/// What a tween animates, and the value it ends on
+#[derive(Clone, Copy, Debug)]
+pub enum TweenProperty {
+    Translation(Vec3),
+    Rotation(Quat),
+    Scale(Vec3),
+    LightIntensity(f32),
+}
+
+/// Whatever a tween can animate on the entity it's playing on
+struct TweenSubject<'a> {
+    transform: Option<&'a mut Transform>,
+    light: Option<&'a mut PointLight>,
+}
+
+/// One property going from wherever it is when the tween starts to a new value
+pub struct Tween {
+    property: TweenProperty,
+    secs: f32,
+    ease: Ease,
+    /// Picked up from the entity on the tween's first frame
+    start: Option<TweenProperty>,
+    elapsed: f32,
+}
+
+impl Tween {
+    /// Gets the property's current value, or `None` if the entity doesn't have it
+    fn current(&self, subject: &TweenSubject) -> Option<TweenProperty> {
+        Some(match self.property {
+            TweenProperty::Translation(_) => TweenProperty::Translation(subject.transform.as_ref()?.translation),
+            TweenProperty::Rotation(_) => TweenProperty::Rotation(subject.transform.as_ref()?.rotation),
+            TweenProperty::Scale(_) => TweenProperty::Scale(subject.transform.as_ref()?.scale),
+            TweenProperty::LightIntensity(_) => TweenProperty::LightIntensity(subject.light.as_ref()?.intensity),
+        })
+    }
+
+    fn advance(&mut self, delta: f32, subject: &mut TweenSubject) -> Option<f32> {
+        if self.start.is_none() {
+            self.start = self.current(subject);
+        }
+
+        self.elapsed += delta;
+        let t = match self.secs > 0.0 {
+            true => self.ease.apply((self.elapsed / self.secs).min(1.0)),
+            false => 1.0,
+        };
+
+        match (self.start, self.property) {
+            (Some(TweenProperty::Translation(from)), TweenProperty::Translation(to)) => {
+                if let Some(tf) = subject.transform.as_deref_mut() {
+                    tf.translation = from.lerp(to, t);
+                }
+            },
+            (Some(TweenProperty::Rotation(from)), TweenProperty::Rotation(to)) => {
+                if let Some(tf) = subject.transform.as_deref_mut() {
+                    tf.rotation = from.slerp(to, t);
+                }
+            },
+            (Some(TweenProperty::Scale(from)), TweenProperty::Scale(to)) => {
+                if let Some(tf) = subject.transform.as_deref_mut() {
+                    tf.scale = from.lerp(to, t);
+                }
+            },
+            (Some(TweenProperty::LightIntensity(from)), TweenProperty::LightIntensity(to)) => {
+                if let Some(light) = subject.light.as_deref_mut() {
+                    light.intensity = from + (to - from) * t;
+                }
+            },
+            // Nothing to animate, so just let the time pass
+            _ => {},
+        }
+
+        (self.elapsed >= self.secs).then_some(self.elapsed - self.secs)
+    }
+}
+
+/// A tween, or several arranged one after another or side by side
+pub enum Track {
+    Tween(Tween),
+    /// Plays each in turn
+    Sequence(VecDeque<Track>),
+    /// Plays them all at once, finishing with the longest
+    Parallel(Vec<Track>),
+    /// Does nothing for a while, to space out a sequence
+    Wait(f32),
+}
+
+impl Track {
+    pub fn tween(property: TweenProperty, secs: f32, ease: Ease) -> Self {
+        Track::Tween(Tween { property, secs, ease, start: None, elapsed: 0.0 })
+    }
+
+    pub fn translation(to: Vec3, secs: f32, ease: Ease) -> Self {
+        Track::tween(TweenProperty::Translation(to), secs, ease)
+    }
+
+    pub fn rotation(to: Quat, secs: f32, ease: Ease) -> Self {
+        Track::tween(TweenProperty::Rotation(to), secs, ease)
+    }
+
+    pub fn scale(to: Vec3, secs: f32, ease: Ease) -> Self {
+        Track::tween(TweenProperty::Scale(to), secs, ease)
+    }
+
+    pub fn light_intensity(to: f32, secs: f32, ease: Ease) -> Self {
+        Track::tween(TweenProperty::LightIntensity(to), secs, ease)
+    }
+
+    pub fn sequence(tracks: impl IntoIterator<Item = Track>) -> Self {
+        Track::Sequence(tracks.into_iter().collect())
+    }
+
+    pub fn parallel(tracks: impl IntoIterator<Item = Track>) -> Self {
+        Track::Parallel(tracks.into_iter().collect())
+    }
+
+    /// Plays `delta` seconds of the track on a transform and light. Once it's finished, returns how much of `delta`
+    /// was left over.
+    pub fn play(&mut self, delta: f32, transform: Option<&mut Transform>, light: Option<&mut PointLight>) -> Option<f32> {
+        self.advance(delta, &mut TweenSubject { transform, light })
+    }
+
+    fn advance(&mut self, delta: f32, subject: &mut TweenSubject) -> Option<f32> {
+        match self {
+            Track::Tween(tween) => tween.advance(delta, subject),
+            Track::Sequence(tracks) => {
+                let mut delta = delta;
+
+                // Time left over from one track carries on into the next
+                while let Some(track) = tracks.front_mut() {
+                    delta = track.advance(delta, subject)?;
+                    tracks.pop_front();
+                }
+
+                Some(delta)
+            },
+            Track::Parallel(tracks) => {
+                let mut left_over = delta;
+                let mut i = 0;
+
+                while i < tracks.len() {
+                    match tracks[i].advance(delta, subject) {
+                        Some(left) => {
+                            left_over = left_over.min(left);
+                            tracks.remove(i);
+                        },
+                        None => i += 1,
+                    }
+                }
+
+                tracks.is_empty().then_some(left_over)
+            },
+            Track::Wait(secs) => {
+                *secs -= delta;
+                (*secs <= 0.0).then(|| -*secs)
+            },
+        }
+    }
+}
+
+/// Run on an entity once its animation has finished
+type OnDone = Box<dyn FnOnce(&mut EntityCommands) + Send + Sync>;
+
+/// Plays a track on an entity's transform or light, then removes itself. For set moves that take a set time; easing
+/// after a target that keeps moving is left to `LerpToTarget`, and hops that report their landings to `QuadJump`.
+#[derive(Component)]
+pub struct Animation {
+    track: Track,
+    /// Sent along with the `AnimationDoneEvent`, so whoever's waiting for it can tell it apart
+    name: Option<&'static str>,
+    on_done: Option<OnDone>,
+}
+
+impl Animation {
+    pub fn new(track: Track) -> Self {
+        Animation { track, name: None, on_done: None }
+    }
+
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Runs `action` on the entity once the whole track has played
+    pub fn on_done<F: 'static + FnOnce(&mut EntityCommands) + Send + Sync>(mut self, action: F) -> Self {
+        self.on_done = Some(Box::new(action));
+        self
+    }
+}
+
+/// Sent when an `Animation` finishes
+pub struct AnimationDoneEvent {
+    pub entity: Entity,
+    pub name: Option<&'static str>,
+}
+
+pub fn play_animations(
+    mut commands: Commands,
+    mut animations: Query<(Entity, &mut Animation, Option<&mut Transform>, Option<&mut PointLight>)>,
+    mut done: EventWriter<AnimationDoneEvent>,
+    time: Res<Time>,
+) {
+    for (id, mut animation, mut transform, mut light) in animations.iter_mut() {
+        if animation.track.play(time.delta_seconds(), transform.as_deref_mut(), light.as_deref_mut()).is_none() {
+            continue;
+        }
+
+        let mut entity = commands.entity(id);
+        entity.remove::<Animation>();
+
+        if let Some(action) = animation.on_done.take() {
+            action(&mut entity);
+        }
+
+        done.send(AnimationDoneEvent { entity: id, name: animation.name });
+    }
+}
@@ -0,0 +1,255 @@
+//! Drives whole turns through the real systems with no window or GPU, by sending the picking events a mouse would.
+
+use bevy::prelude::*;
+use bevy_mod_picking::{PickingEvent, SelectionEvent};
+use foretold::game::*;
+use foretold::game::ai::*;
+use foretold::game::notation::*;
+use foretold::game::outro::EndGameEvent;
+use foretold::game::playing::{RedMove, TypedMoveEvent};
+use foretold::game::setup::CheckerMesh;
+use foretold::plugins::*;
+use foretold::rng::GameRng;
+use foretold::settings::Settings;
+
+/// More updates than any turn needs once animations take no time
+const MAX_UPDATES: usize = 1000;
+
+#[derive(Default)]
+struct Winner(Option<Team>);
+
+fn record_winner(mut events: EventReader<EndGameEvent>, mut winner: ResMut<Winner>) {
+    for e in events.iter() {
+        winner.0 = Some(e.0);
+    }
+}
+
+fn headless_app() -> App {
+    let mut app = App::new();
+    app
+    .add_plugins(MinimalPlugins)
+    .add_plugin(RulesPlugin)
+    .add_plugin(AiPlugin)
+    .insert_resource(GameRng::new(7))
+    // Bevy 0.6 can't be handed the time, so instead animations take none and every hop lands on the update it starts
+    .insert_resource(Settings { animation_speed: f32::INFINITY, ..Default::default() })
+    .init_resource::<Winner>()
+    // The loss checks send the event as they enter a state, after anything in the update stage has run
+    .add_system_to_stage(CoreStage::PostUpdate, record_winner);
+
+    // Runs the startup systems that spawn the board
+    app.update();
+    app
+}
+
+fn state(app: &App) -> GameState {
+    *app.world.get_resource::<State<GameState>>().unwrap().current()
+}
+
+fn set_state(app: &mut App, state: GameState) {
+    app.world.get_resource_mut::<State<GameState>>().unwrap().set(state).unwrap();
+    app.update();
+}
+
+/// Keeps updating until `done` holds
+fn run_until(app: &mut App, done: impl Fn(&App) -> bool) {
+    for _ in 0..MAX_UPDATES {
+        if done(app) {
+            return;
+        }
+
+        app.update();
+    }
+
+    panic!("Gave up waiting in {:?}", state(app));
+}
+
+fn board(app: &mut App) -> Board {
+    let mut checkers = app.world.query::<&Checker>();
+    Board::from_checkers(checkers.iter(&app.world))
+}
+
+fn pick(app: &mut App, entity: Entity) {
+    app.world.get_resource_mut::<bevy::ecs::event::Events<PickingEvent>>().unwrap()
+    .send(PickingEvent::Selection(SelectionEvent::JustSelected(entity)));
+    app.update();
+}
+
+/// Clicks the checker a move starts from and then its destination, like the player would
+fn play(app: &mut App, mov: &Move) {
+    let checker = app.world.get_resource::<CheckerMap>().unwrap().get_place_checker(mov.start).unwrap();
+    // Picking hits the checker's mesh rather than the checker itself
+    let mesh = app.world.spawn().insert(CheckerMesh(checker)).id();
+    let place = app.world.get_resource::<PlaceMap>().unwrap().get(mov.dest());
+
+    pick(app, mesh);
+    pick(app, place);
+}
+
+fn type_move(app: &mut App, text: &str) {
+    app.world.get_resource_mut::<bevy::ecs::event::Events<TypedMoveEvent>>().unwrap()
+    .send(TypedMoveEvent(text.to_string()));
+    app.update();
+}
+
+fn red_moves(app: &mut App) -> Vec<Move> {
+    board(app).legal_moves(Team::Red)
+}
+
+fn remove_team(app: &mut App, team: Team) {
+    let mut removed = vec![];
+
+    {
+        let mut map = app.world.get_resource_mut::<CheckerMap>().unwrap();
+        for pos in all_squares() {
+            if let Some((checker, t)) = map.get_place(pos) {
+                if t == team {
+                    map.set_place(pos, None);
+                    removed.push(checker);
+                }
+            }
+        }
+    }
+
+    for checker in removed {
+        app.world.despawn(checker);
+    }
+}
+
+fn all_squares() -> Vec<IVec2> {
+    (1..=32).filter_map(square_pos).collect()
+}
+
+#[test]
+fn foretold_move_is_played_after_blacks_reply() {
+    let mut app = headless_app();
+    set_state(&mut app, GameState::BlackForesight);
+
+    // Nothing Black can do on its first move reaches the third row
+    let mov = red_moves(&mut app)[0].clone();
+    play(&mut app, &mov);
+    assert_eq!(state(&app), GameState::Black);
+
+    run_until(&mut app, |app| state(app) == GameState::BlackForesight);
+
+    let map = app.world.get_resource::<CheckerMap>().unwrap();
+    assert_eq!(map.get_place_team(mov.dest()), Some(Team::Red));
+    assert_eq!(map.get_place_team(mov.start), None);
+
+    let log = &app.world.get_resource::<GameLog>().unwrap().0;
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].team, Team::Black);
+    assert_eq!(log[1].team, Team::Red);
+    assert_eq!(log[1].mov, mov);
+    assert!(log[1].replaced.is_none());
+}
+
+#[test]
+fn invalid_destination_is_refused() {
+    let mut app = headless_app();
+    set_state(&mut app, GameState::BlackForesight);
+
+    let mut mov = red_moves(&mut app)[0].clone();
+    mov.jumps = vec![mov.start + IVec2::new(0, 4)];
+    play(&mut app, &mov);
+
+    assert_eq!(state(&app), GameState::BlackForesight);
+}
+
+#[test]
+fn typed_move_is_foretold() {
+    let mut app = headless_app();
+    set_state(&mut app, GameState::BlackForesight);
+
+    type_move(&mut app, "21-17");
+    run_until(&mut app, |app| state(app) != GameState::BlackForesight);
+
+    let red_move = app.world.get_resource::<RedMove>().unwrap();
+    assert_eq!(red_move.start, square_pos(21).unwrap());
+    assert_eq!(red_move.jumps, vec![square_pos(17).unwrap()]);
+}
+
+#[test]
+fn illegal_typed_move_is_refused() {
+    let mut app = headless_app();
+    set_state(&mut app, GameState::BlackForesight);
+
+    // 21 is one of Red's, but 18 isn't next to it
+    type_move(&mut app, "21-18");
+    app.update();
+
+    assert_eq!(state(&app), GameState::BlackForesight);
+    assert_eq!(app.world.get_resource::<Header>().unwrap().0, "21-18 is not a move you can make");
+}
+
+#[test]
+fn invalidated_move_is_corrected() {
+    let mut app = headless_app();
+    // Black goes out of its way to spoil the foretold move whenever it can
+    app.insert_resource(AiConfig { invalidate_bonus: 100.0, ..Default::default() });
+    set_state(&mut app, GameState::BlackForesight);
+
+    for _ in 0..10 {
+        let mov = red_moves(&mut app)[0].clone();
+        play(&mut app, &mov);
+
+        run_until(&mut app, |app| matches!(state(app), GameState::BlackForesight | GameState::RedCorrection));
+
+        if state(&app) == GameState::RedCorrection {
+            let correction = red_moves(&mut app)[0].clone();
+            play(&mut app, &correction);
+            assert_eq!(state(&app), GameState::BlackForesight);
+
+            let log = &app.world.get_resource::<GameLog>().unwrap().0;
+            let last = log.last().unwrap();
+            assert_eq!(last.team, Team::Red);
+            assert_eq!(last.mov, correction);
+            assert_eq!(last.replaced.as_ref(), Some(&mov));
+            return;
+        }
+    }
+
+    panic!("Black never invalidated a foretold move");
+}
+
+#[test]
+fn red_without_moves_loses() {
+    let mut app = headless_app();
+    remove_team(&mut app, Team::Red);
+    set_state(&mut app, GameState::BlackForesight);
+
+    run_until(&mut app, |app| state(app) == GameState::Outro);
+    assert_eq!(app.world.get_resource::<Winner>().unwrap().0, Some(Team::Black));
+}
+
+#[test]
+fn black_without_moves_loses() {
+    let mut app = headless_app();
+    remove_team(&mut app, Team::Black);
+    set_state(&mut app, GameState::BlackForesight);
+
+    let mov = red_moves(&mut app)[0].clone();
+    play(&mut app, &mov);
+
+    run_until(&mut app, |app| state(app) == GameState::Outro);
+    assert_eq!(app.world.get_resource::<Winner>().unwrap().0, Some(Team::Red));
+}
+
+#[test]
+fn escape_skips_the_intro_without_rendering() {
+    let mut app = App::new();
+    app
+    .add_plugins(MinimalPlugins)
+    .add_plugin(bevy::asset::AssetPlugin)
+    .add_plugin(RulesPlugin)
+    .add_plugin(DialoguePlugin)
+    .insert_resource(GameRng::new(7));
+    app.update();
+
+    set_state(&mut app, GameState::Intro);
+    app.world.get_resource_mut::<Input<KeyCode>>().unwrap().press(KeyCode::Escape);
+    app.update();
+    app.update();
+
+    assert_eq!(state(&app), GameState::BlackForesight);
+}
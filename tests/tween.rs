@@ -0,0 +1,81 @@
+//! Steps tweens by hand, so the timing can be checked exactly without an app.
+
+use bevy::prelude::*;
+use foretold::tween::*;
+
+const EASES: [Ease; 10] = [
+    Ease::Linear,
+    Ease::QuadIn,
+    Ease::QuadOut,
+    Ease::QuadInOut,
+    Ease::CubicIn,
+    Ease::CubicOut,
+    Ease::CubicInOut,
+    Ease::SineInOut,
+    Ease::BackOut,
+    Ease::BounceOut,
+];
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{} is not close to {}", a, b);
+}
+
+#[test]
+fn every_ease_starts_at_zero_and_ends_at_one() {
+    for ease in EASES {
+        assert_close(ease.apply(0.0), 0.0);
+        assert_close(ease.apply(1.0), 1.0);
+    }
+}
+
+#[test]
+fn tween_lands_exactly_on_its_target() {
+    let mut tf = Transform::default();
+    let mut track = Track::translation(Vec3::X, 1.0, Ease::BackOut);
+
+    assert_eq!(track.play(0.5, Some(&mut tf), None), None);
+    let left = track.play(0.7, Some(&mut tf), None);
+
+    assert_close(left.unwrap(), 0.2);
+    assert_eq!(tf.translation, Vec3::X);
+}
+
+#[test]
+fn sequence_carries_left_over_time_into_the_next_track() {
+    let mut tf = Transform::default();
+    let mut track = Track::sequence([
+        Track::translation(Vec3::X, 1.0, Ease::Linear),
+        Track::Wait(0.5),
+        Track::translation(Vec3::ZERO, 1.0, Ease::Linear),
+    ]);
+
+    // A whole second and a half in one step finishes the first move and the wait
+    assert_eq!(track.play(1.5, Some(&mut tf), None), None);
+    assert_eq!(tf.translation, Vec3::X);
+
+    // Anything past the wait goes straight into the last move
+    assert_eq!(track.play(0.25, Some(&mut tf), None), None);
+    assert_close(tf.translation.x, 0.75);
+
+    let left = track.play(1.0, Some(&mut tf), None);
+    assert_close(left.unwrap(), 0.25);
+    assert_eq!(tf.translation, Vec3::ZERO);
+}
+
+#[test]
+fn parallel_finishes_with_its_longest_track() {
+    let mut tf = Transform::default();
+    let mut light = PointLight { intensity: 0.0, ..Default::default() };
+    let mut track = Track::parallel([
+        Track::translation(Vec3::Y, 1.0, Ease::Linear),
+        Track::light_intensity(100.0, 2.0, Ease::Linear),
+    ]);
+
+    assert_eq!(track.play(1.5, Some(&mut tf), Some(&mut light)), None);
+    assert_eq!(tf.translation, Vec3::Y);
+    assert_close(light.intensity, 75.0);
+
+    let left = track.play(1.0, Some(&mut tf), Some(&mut light));
+    assert_close(left.unwrap(), 0.5);
+    assert_close(light.intensity, 100.0);
+}